@@ -0,0 +1,106 @@
+//! Measures the time and heap allocations taken to parse large mount tables, and to reread the
+//! mounts of the running system.
+//!
+//! Run with `cargo bench --bench parse`.
+
+extern crate proc_mounts;
+
+use proc_mounts::{MountInfoList, MountList};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) { System.dealloc(ptr, layout) }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const LINES: usize = 10_000;
+const ITERATIONS: u32 = 20;
+
+fn bench<F: FnMut()>(name: &str, lines: usize, mut parse: F) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        parse();
+    }
+
+    let elapsed = start.elapsed() / ITERATIONS;
+    let allocations = (ALLOCATIONS.load(Ordering::Relaxed) - allocations) / ITERATIONS as usize;
+    println!(
+        "{}: {:?} per parse, {:.1} allocations per line",
+        name,
+        elapsed,
+        allocations as f64 / lines.max(1) as f64
+    );
+}
+
+fn main() {
+    let mut mounts = String::new();
+    let mut mountinfo = String::new();
+    for id in 0..LINES {
+        if id % 2 == 0 {
+            mounts.push_str(&format!(
+                "/dev/sda{0} /mnt/data{0} ext4 rw,nosuid,nodev,relatime,errors=remount-ro 0 0\n",
+                id
+            ));
+            mountinfo.push_str(&format!(
+                "{0} 1 8:{0} / /mnt/data{0} rw,relatime shared:{0} - ext4 /dev/sda{0} rw\n",
+                id
+            ));
+        } else {
+            mounts.push_str(&format!(
+                "/dev/sdb{0} /media/My\\040Disk{0} btrfs rw,subvol=/my\\040subvol{0} 0 0\n",
+                id
+            ));
+            mountinfo.push_str(&format!(
+                "{0} 1 8:{0} /sub /media/My\\040Disk{0} rw - btrfs /dev/sdb{0} \
+                 rw,subvol=/a\\040b\n",
+                id
+            ));
+        }
+    }
+
+    bench("MountList::parse_from", LINES, || {
+        MountList::parse_from(mounts.lines()).unwrap();
+    });
+
+    bench("MountList::iter_refs", LINES, || {
+        for mount in MountList::iter_refs(&mounts) {
+            mount.unwrap();
+        }
+    });
+
+    bench("MountInfoList::parse_from", LINES, || {
+        MountInfoList::parse_from(mountinfo.lines()).unwrap();
+    });
+
+    // These read the mounts of the running system, and so are only comparable to each other.
+    let lines = MountList::new().unwrap().len();
+    bench("MountList::new", lines, || {
+        MountList::new().unwrap();
+    });
+
+    let mut buffer = String::new();
+    bench("MountList::refresh_into", lines, || {
+        MountList::refresh_into(&mut buffer).unwrap();
+    });
+}
@@ -0,0 +1,20 @@
+extern crate proc_mounts;
+
+use proc_mounts::{MountList, MountWatcher};
+use std::io;
+
+fn main() -> io::Result<()> {
+    let mut watcher = MountWatcher::new()?;
+    let mut mounts = MountList::new()?;
+
+    loop {
+        let new_mounts = watcher.wait()?;
+        for mount in &new_mounts.0 {
+            if !mounts.0.contains(mount) {
+                println!("mounted {} at {}", mount.source.display(), mount.dest.display());
+            }
+        }
+
+        mounts = new_mounts;
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    io,
+};
+
+/// An error which occurred while parsing a line of a mount or swap table.
+///
+/// Line numbers start at 1, and count every line read from the source, including comments.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub enum ParseError {
+    /// A required field was not found on the line.
+    MissingField { field: &'static str, line_number: usize },
+    /// A numeric field contained a value which is not a number.
+    InvalidNumber { field: &'static str, line_number: usize, value: String },
+    /// An octal escape sequence ended before three octal digits were given.
+    TruncatedOctal { line_number: usize },
+    /// An octal escape sequence contained a non-octal digit, or a value larger than a byte.
+    InvalidOctal { line_number: usize, sequence: String },
+    /// A field contained a value which is not valid UTF-8.
+    NonUtf8 { field: &'static str, line_number: usize },
+    /// A `/dev/disk/by-*` source could not be parsed.
+    InvalidDevicePath { path: String, line_number: usize, reason: String },
+    /// A `/dev/disk/by-*` source does not point to an existing device.
+    DeviceNotFound { path: String, line_number: usize },
+}
+
+impl ParseError {
+    /// The line on which the error occurred.
+    pub fn line_number(&self) -> usize {
+        match *self {
+            ParseError::MissingField { line_number, .. }
+            | ParseError::InvalidNumber { line_number, .. }
+            | ParseError::TruncatedOctal { line_number }
+            | ParseError::InvalidOctal { line_number, .. }
+            | ParseError::NonUtf8 { line_number, .. }
+            | ParseError::InvalidDevicePath { line_number, .. }
+            | ParseError::DeviceNotFound { line_number, .. } => line_number,
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            ParseError::MissingField { field, line_number } => {
+                write!(fmt, "line {}: missing {}", line_number, field)
+            }
+            ParseError::InvalidNumber { field, line_number, value } => {
+                write!(fmt, "line {}: {} value is not a number: {:?}", line_number, field, value)
+            }
+            ParseError::TruncatedOctal { line_number } => {
+                write!(fmt, "line {}: truncated octal code", line_number)
+            }
+            ParseError::InvalidOctal { line_number, sequence } => {
+                write!(fmt, "line {}: invalid octal code: {}", line_number, sequence)
+            }
+            ParseError::NonUtf8 { field, line_number } => {
+                write!(fmt, "line {}: non-utf8 {} is unsupported", line_number, field)
+            }
+            ParseError::InvalidDevicePath { path, line_number, reason } => {
+                write!(fmt, "line {}: {}: {}", line_number, path, reason)
+            }
+            ParseError::DeviceNotFound { path, line_number } => {
+                write!(fmt, "line {}: device path for {} was not found", line_number, path)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl From<ParseError> for io::Error {
+    fn from(error: ParseError) -> Self {
+        let kind = match error {
+            ParseError::DeviceNotFound { .. } => io::ErrorKind::NotFound,
+            _ => io::ErrorKind::InvalidData,
+        };
+
+        io::Error::new(kind, error)
+    }
+}
@@ -23,9 +23,20 @@
 
 #[macro_use]
 extern crate lazy_static;
+#[cfg(feature = "libc")]
+extern crate libc;
 extern crate partition_identity;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+#[cfg(feature = "tokio")]
+extern crate tokio;
 
+mod error;
 mod mounts;
+#[cfg(feature = "tokio")]
+mod nonblocking;
 mod swaps;
 
 use std::collections::hash_map::DefaultHasher;
@@ -40,7 +51,7 @@ use std::{
     time::Duration,
 };
 
-pub use self::{mounts::*, swaps::*};
+pub use self::{error::*, mounts::*, swaps::*};
 
 lazy_static! {
     /// Static list of mounts that is dynamically updated in the background.
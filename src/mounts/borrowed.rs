@@ -0,0 +1,142 @@
+use super::MountInfo;
+use std::{
+    borrow::Cow,
+    ffi::OsStr,
+    hash::{Hash, Hasher},
+    io,
+    path::PathBuf,
+};
+use ParseError;
+
+/// A mount entry which borrows its fields from the line it was parsed from.
+///
+/// Parsing into a `MountInfoRef` avoids allocating for each field, and so is suited to callers
+/// which re-read the mounts frequently. Only a source or destination which contains octal
+/// escapes is copied, to store its unescaped form. Unlike `MountInfo`, `/dev/disk/by-*` sources
+/// are not resolved to their device paths until converted with `to_owned`.
+///
+/// Entries are compared and hashed by their fields alone, regardless of the line they were
+/// parsed from.
+#[derive(Debug, Clone)]
+pub struct MountInfoRef<'a> {
+    /// The source which is mounted.
+    pub source: Cow<'a, OsStr>,
+    /// Where the source is mounted.
+    pub dest: Cow<'a, OsStr>,
+    /// The type of the mounted file system, with any octal escapes left intact.
+    pub fstype: &'a str,
+    /// Options specified for this file system, separated by commas, with any octal escapes left
+    /// intact.
+    pub options: &'a str,
+    /// Defines if the file system should be dumped.
+    pub dump: i32,
+    /// Defines if the file system should be checked, and in what order.
+    pub pass: i32,
+    line_number: usize,
+}
+
+impl<'a> MountInfoRef<'a> {
+    /// Parse a `/proc/mounts`-like line, reporting errors at the given `line_number`.
+    pub(crate) fn parse_numbered(
+        line: &'a str,
+        line_number: usize,
+    ) -> Result<MountInfoRef<'a>, ParseError> {
+        let mut parts = line.split_whitespace();
+
+        let missing = |field| ParseError::MissingField { field, line_number };
+
+        let parse_number = |field, value: &str| {
+            value.parse::<i32>().map_err(|_| ParseError::InvalidNumber {
+                field,
+                line_number,
+                value: value.to_owned(),
+            })
+        };
+
+        let source = parts.next().ok_or_else(|| missing("source"))?;
+        let dest = parts.next().ok_or_else(|| missing("dest"))?;
+        let fstype = parts.next().ok_or_else(|| missing("type"))?;
+        let options = parts.next().ok_or_else(|| missing("options"))?;
+
+        let dump = parts.next().map_or(Ok(0), |value| parse_number("dump", value))?;
+        let pass = parts.next().map_or(Ok(0), |value| parse_number("pass", value))?;
+
+        Ok(MountInfoRef {
+            source: unescape(source, line_number)?,
+            dest: unescape(dest, line_number)?,
+            fstype,
+            options,
+            dump,
+            pass,
+            line_number,
+        })
+    }
+
+    /// Copy the fields into an owned `MountInfo`, resolving `/dev/disk/by-*` sources.
+    ///
+    /// An error is returned if a field is not valid UTF-8 once unescaped, or if a
+    /// `/dev/disk/by-*` source cannot be resolved.
+    pub fn to_owned(&self) -> io::Result<MountInfo> { self.to_info().map_err(io::Error::from) }
+
+    pub(crate) fn to_info(&self) -> Result<MountInfo, ParseError> {
+        let line_number = self.line_number;
+
+        let path = self
+            .source
+            .to_str()
+            .ok_or_else(|| ParseError::NonUtf8 { field: "source", line_number })?;
+
+        let source = if path.starts_with("/dev/disk/by-") {
+            MountInfo::fetch_from_disk_by_path(path, line_number)?
+        } else {
+            PathBuf::from(path)
+        };
+
+        let path =
+            self.dest.to_str().ok_or_else(|| ParseError::NonUtf8 { field: "dest", line_number })?;
+
+        let mut buffer = Vec::new();
+
+        Ok(MountInfo {
+            source,
+            dest: PathBuf::from(path),
+            fstype: MountInfo::parse_string(self.fstype, "type", line_number, &mut buffer)?,
+            options: MountInfo::parse_options(self.options, "options", line_number, &mut buffer)?,
+            dump: self.dump,
+            pass: self.pass,
+        })
+    }
+}
+
+impl<'a> PartialEq for MountInfoRef<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+            && self.dest == other.dest
+            && self.fstype == other.fstype
+            && self.options == other.options
+            && self.dump == other.dump
+            && self.pass == other.pass
+    }
+}
+
+impl<'a> Eq for MountInfoRef<'a> {}
+
+impl<'a> Hash for MountInfoRef<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.source.hash(state);
+        self.dest.hash(state);
+        self.fstype.hash(state);
+        self.options.hash(state);
+        self.dump.hash(state);
+        self.pass.hash(state);
+    }
+}
+
+/// Unescape the octal sequences of a field, copying it only if it contains any.
+fn unescape(value: &str, line_number: usize) -> Result<Cow<OsStr>, ParseError> {
+    if value.contains('\\') {
+        MountInfo::parse_value(value, line_number).map(Cow::Owned)
+    } else {
+        Ok(Cow::Borrowed(OsStr::new(value)))
+    }
+}
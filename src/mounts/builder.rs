@@ -0,0 +1,67 @@
+use super::MountInfo;
+use std::path::PathBuf;
+
+/// Constructs a `MountInfo` programmatically.
+///
+/// ```rust
+/// extern crate proc_mounts;
+///
+/// use proc_mounts::MountInfo;
+///
+/// let info = MountInfo::builder("/dev/sda1", "/boot/efi", "vfat")
+///     .options(vec!["rw", "relatime"])
+///     .option("umask=0077")
+///     .pass(1)
+///     .build();
+///
+/// assert_eq!(info.to_string(), "/dev/sda1 /boot/efi vfat rw,relatime,umask=0077 0 1");
+/// ```
+#[derive(Debug, Clone)]
+pub struct MountInfoBuilder {
+    info: MountInfo,
+}
+
+impl MountInfoBuilder {
+    /// Begin a mount of `source` at `dest`, with no options, and dump and pass set to `0`.
+    pub fn new<S: Into<PathBuf>, D: Into<PathBuf>, F: Into<String>>(
+        source: S,
+        dest: D,
+        fstype: F,
+    ) -> Self {
+        Self {
+            info: MountInfo {
+                source: source.into(),
+                dest: dest.into(),
+                fstype: fstype.into(),
+                ..MountInfo::default()
+            },
+        }
+    }
+
+    /// Append an option.
+    pub fn option<O: Into<String>>(mut self, option: O) -> Self {
+        self.info.options.push(option.into());
+        self
+    }
+
+    /// Append each of the given options.
+    pub fn options<I: IntoIterator<Item = O>, O: Into<String>>(mut self, options: I) -> Self {
+        self.info.options.extend(options.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set whether the file system should be dumped.
+    pub fn dump(mut self, dump: i32) -> Self {
+        self.info.dump = dump;
+        self
+    }
+
+    /// Set whether the file system should be checked, and in what order.
+    pub fn pass(mut self, pass: i32) -> Self {
+        self.info.pass = pass;
+        self
+    }
+
+    /// Create the `MountInfo`.
+    pub fn build(self) -> MountInfo { self.info }
+}
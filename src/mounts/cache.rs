@@ -0,0 +1,55 @@
+use super::MountList;
+use std::{
+    io::{self, BufRead},
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+/// A parsed list of mounts which is kept in memory, and only reread when refreshed.
+///
+/// The cache dereferences to its `MountList`, so lookups may be made on it directly.
+#[derive(Debug, Clone)]
+pub struct MountCache {
+    path:   PathBuf,
+    mounts: MountList,
+}
+
+impl MountCache {
+    /// Cache the mounts of `/proc/mounts`.
+    pub fn new() -> io::Result<Self> { Self::new_from_file("/proc/mounts") }
+
+    /// Cache the mounts of any mount-tab-like file, which is reread on each refresh.
+    pub fn new_from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mounts = MountList::new_from_file(&path)?;
+        Ok(Self { path, mounts })
+    }
+
+    /// Reread the mounts from the file, returning true if they differ from the cached list.
+    pub fn refresh(&mut self) -> io::Result<bool> {
+        let mounts = MountList::new_from_file(&self.path)?;
+        Ok(self.replace(mounts))
+    }
+
+    /// Parse the mounts from the given `reader` rather than the file, returning true if they
+    /// differ from the cached list.
+    pub fn refresh_from_reader<R: BufRead>(&mut self, reader: R) -> io::Result<bool> {
+        let mounts = MountList::new_from_reader(reader)?;
+        Ok(self.replace(mounts))
+    }
+
+    /// The cached list of mounts.
+    pub fn mounts(&self) -> &MountList { &self.mounts }
+
+    fn replace(&mut self, mounts: MountList) -> bool {
+        let changed = self.mounts != mounts;
+        self.mounts = mounts;
+        changed
+    }
+}
+
+impl Deref for MountCache {
+    type Target = MountList;
+
+    fn deref(&self) -> &Self::Target { &self.mounts }
+}
@@ -0,0 +1,100 @@
+use super::{MountInfo, MountList};
+use libc;
+use std::{
+    fs, io,
+    os::unix::fs::{FileTypeExt, MetadataExt},
+    path::{Path, PathBuf},
+};
+
+/// Tags which name a device in `/etc/fstab`, and the directories which resolve them.
+const SOURCE_TAGS: &[(&str, &str)] = &[
+    ("UUID=", "/dev/disk/by-uuid"),
+    ("PARTUUID=", "/dev/disk/by-partuuid"),
+    ("LABEL=", "/dev/disk/by-label"),
+    ("PARTLABEL=", "/dev/disk/by-partlabel"),
+];
+
+impl MountInfo {
+    /// The major and minor device numbers of the file system mounted at `dest`.
+    ///
+    /// This is the same device number which `/proc/self/mountinfo` and `/proc/diskstats`
+    /// report. Pseudo file systems, such as `proc` or `tmpfs`, are not backed by a block device,
+    /// and so return the anonymous device number which the kernel assigned them, with a major
+    /// number of `0`.
+    pub fn device_id(&self) -> io::Result<(u32, u32)> {
+        let dev = fs::metadata(&self.dest)?.dev();
+        Ok((libc::major(dev), libc::minor(dev)))
+    }
+
+    /// Returns true if the source is a block device, by stat'ing it.
+    ///
+    /// Unlike `has_block_source`, which only considers whether the source looks like a path,
+    /// this performs a filesystem lookup, and so excludes sources which are regular files or
+    /// directories. An error is returned if a path-like source does not exist. Sources which are
+    /// not paths, such as `tmpfs`, are never block devices.
+    pub fn source_is_block_device(&self) -> io::Result<bool> {
+        if !self.has_block_source() {
+            return Ok(false);
+        }
+
+        Ok(fs::metadata(&self.source)?.file_type().is_block_device())
+    }
+
+    /// Returns true if both sources refer to the same device, even where one names it
+    /// differently, such as an `/etc/fstab` entry with `UUID=...` or `/dev/disk/by-uuid/...`
+    /// against the `/dev/sda2` of `/proc/mounts`.
+    ///
+    /// This performs filesystem lookups: `UUID=`, `PARTUUID=`, `LABEL=`, and `PARTLABEL=` tags
+    /// are resolved through `/dev/disk/by-*`, and each absolute source is canonicalized. Sources
+    /// which are not paths, such as `tmpfs`, are compared as they are. An error is returned if
+    /// a source does not exist.
+    pub fn same_device_as(&self, other: &MountInfo) -> io::Result<bool> {
+        Ok(self.canonical_source()? == other.canonical_source()?)
+    }
+
+    fn canonical_source(&self) -> io::Result<PathBuf> {
+        let source = self.source.to_string_lossy();
+        let tagged = SOURCE_TAGS.iter().find(|(tag, _)| source.starts_with(tag));
+        let source = match tagged {
+            Some((tag, dir)) => Path::new(dir).join(&source[tag.len()..]),
+            None => self.source.clone(),
+        };
+
+        if source.is_absolute() {
+            fs::canonicalize(source)
+        } else {
+            Ok(source)
+        }
+    }
+}
+
+impl MountList {
+    /// Find the first mount whose source is the block device with the given device numbers.
+    ///
+    /// Each source within `/dev` is stat'd, so device paths which are symlinks, such as those
+    /// in `/dev/mapper`, still match. Sources which are not block devices, or which no longer
+    /// exist, are skipped.
+    pub fn mount_by_device(&self, major: u32, minor: u32) -> io::Result<Option<&MountInfo>> {
+        for mount in &self.0 {
+            if !mount.source.starts_with("/dev") {
+                continue;
+            }
+
+            let metadata = match fs::metadata(&mount.source) {
+                Ok(metadata) => metadata,
+                Err(ref why) if why.kind() == io::ErrorKind::NotFound => continue,
+                Err(why) => return Err(why),
+            };
+
+            let rdev = metadata.rdev();
+            if metadata.file_type().is_block_device()
+                && libc::major(rdev) == major
+                && libc::minor(rdev) == minor
+            {
+                return Ok(Some(mount));
+            }
+        }
+
+        Ok(None)
+    }
+}
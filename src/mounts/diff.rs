@@ -0,0 +1,58 @@
+use super::{MountInfo, MountList};
+use std::{collections::HashMap, path::Path, ptr};
+
+/// The differences between two lists of mounts, with mounts matched by their destination.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct MountDiff<'a> {
+    /// Mounts whose destination only exists in the new list.
+    pub added: Vec<&'a MountInfo>,
+    /// Mounts whose destination only exists in the old list.
+    pub removed: Vec<&'a MountInfo>,
+    /// Mounts whose destination exists in both lists, but which differ, as `(old, new)` pairs.
+    pub changed: Vec<(&'a MountInfo, &'a MountInfo)>,
+}
+
+impl<'a> MountDiff<'a> {
+    /// Compare the `old` list of mounts to the `new` list.
+    ///
+    /// If a destination is listed more than once, the last entry is used, as it is the one
+    /// which is visible.
+    pub fn new(old: &'a MountList, new: &'a MountList) -> MountDiff<'a> {
+        fn by_dest(list: &MountList) -> HashMap<&Path, &MountInfo> {
+            list.0.iter().map(|mount| (mount.dest.as_path(), mount)).collect()
+        }
+
+        let old_dests = by_dest(old);
+        let new_dests = by_dest(new);
+        let mut diff = MountDiff::default();
+
+        // Mounts which are hidden beneath a later mount at the same destination are skipped.
+        let is_visible = |dests: &HashMap<&Path, &MountInfo>, mount: &MountInfo| {
+            dests.get(mount.dest.as_path()).map_or(false, |&visible| ptr::eq(visible, mount))
+        };
+
+        for mount in old.0.iter().filter(|mount| is_visible(&old_dests, mount)) {
+            if !new_dests.contains_key(mount.dest.as_path()) {
+                diff.removed.push(mount);
+            }
+        }
+
+        for mount in new.0.iter().filter(|mount| is_visible(&new_dests, mount)) {
+            match old_dests.get(mount.dest.as_path()) {
+                Some(&old_mount) => {
+                    if old_mount != mount {
+                        diff.changed.push((old_mount, mount));
+                    }
+                }
+                None => diff.added.push(mount),
+            }
+        }
+
+        diff
+    }
+
+    /// Returns true if there are no differences.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
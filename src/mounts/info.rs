@@ -1,30 +1,52 @@
+use super::{
+    encode_octal, MountInfoBuilder, MountInfoRef, MountOptions, NetworkSource, OverlayPaths,
+};
 use partition_identity::PartitionID;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::{
     char,
-    ffi::OsString,
+    collections::BTreeSet,
+    convert::TryFrom,
+    ffi::{OsStr, OsString},
     fmt::{self, Display, Formatter},
-    io::{self, Error, ErrorKind},
-    os::unix::ffi::OsStringExt,
-    path::PathBuf,
-    str::FromStr,
+    fs, io,
+    os::unix::ffi::{OsStrExt, OsStringExt},
+    path::{Path, PathBuf},
+    str::{self, FromStr},
 };
+use ParseError;
 
 /// A mount entry which contains information regarding how and where a source
 /// is mounted.
 #[derive(Debug, Default, Clone, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MountInfo {
     /// The source which is mounted.
-    pub source: PathBuf,
+    pub source:  PathBuf,
     /// Where the source is mounted.
-    pub dest: PathBuf,
+    pub dest:    PathBuf,
     /// The type of the mounted file system.
-    pub fstype: String,
+    pub fstype:  String,
     /// Options specified for this file system.
     pub options: Vec<String>,
     /// Defines if the file system should be dumped.
-    pub dump: i32,
+    pub dump:    i32,
     /// Defines if the file system should be checked, and in what order.
-    pub pass: i32,
+    pub pass:    i32,
+}
+
+/// The fields of a mount entry as they were written in the mount tab, before octal unescaping.
+#[derive(Debug, Default, Clone, Hash, Eq, PartialEq)]
+pub struct RawTokens {
+    /// The source, as written.
+    pub source:  String,
+    /// The destination, as written.
+    pub dest:    String,
+    /// The file system type, as written.
+    pub fstype:  String,
+    /// The comma-separated options, as written.
+    pub options: String,
 }
 
 impl Display for MountInfo {
@@ -32,101 +54,586 @@ impl Display for MountInfo {
         write!(
             fmt,
             "{} {} {} {} {} {}",
-            self.source.display(),
-            self.dest.display(),
-            self.fstype,
-            if self.options.is_empty() { "defaults".into() } else { self.options.join(",") },
+            encode_octal(self.source.as_os_str()),
+            encode_octal(self.dest.as_os_str()),
+            encode_octal(OsStr::new(&self.fstype)),
+            if self.options.is_empty() {
+                "defaults".into()
+            } else {
+                // Commas separate the options, so those within an option must also be escaped.
+                let options = self
+                    .options
+                    .iter()
+                    .map(|option| encode_octal(OsStr::new(option)).replace(',', "\\054"));
+                options.collect::<Vec<_>>().join(",")
+            },
             self.dump,
             self.pass
         )
     }
 }
 
+/// Options of a cgroup v1 mount which do not name a controller.
+const NON_CONTROLLER_OPTIONS: &[&str] = &[
+    "async",
+    "clone_children",
+    "cpuset_v2_mode",
+    "defaults",
+    "dev",
+    "dirsync",
+    "exec",
+    "lazytime",
+    "noatime",
+    "nodev",
+    "nodiratime",
+    "noexec",
+    "none",
+    "noprefix",
+    "norelatime",
+    "nosuid",
+    "relatime",
+    "ro",
+    "rw",
+    "strictatime",
+    "suid",
+    "sync",
+    "xattr",
+];
+
+/// Devices whose names end with a number, which is not a partition number.
+const DIGIT_SUFFIXED_DISKS: &[&str] = &["loop", "md", "mmcblk", "nbd", "nvme", "sr", "zram"];
+
+/// Returns true if the device name is that of a loop device, such as `loop0`.
+fn is_loop_device(name: &str) -> bool {
+    name.starts_with("loop") && name.len() > 4 && name[4..].bytes().all(|b| b.is_ascii_digit())
+}
+
 impl FromStr for MountInfo {
     type Err = io::Error;
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let mut parts = line.split_whitespace();
+        Self::parse_numbered(line, 1).map_err(io::Error::from)
+    }
+}
+
+/// Parse a single `/proc/mounts`-like line, as with `FromStr`.
+///
+/// ```rust
+/// extern crate proc_mounts;
+///
+/// use proc_mounts::MountInfo;
+/// use std::{convert::TryInto, io};
+///
+/// fn main() -> io::Result<()> {
+///     let mount: MountInfo = "/dev/sda1 /boot/efi vfat rw,relatime 0 0".try_into()?;
+///     assert_eq!(mount.fstype, "vfat");
+///     Ok(())
+/// }
+/// ```
+impl<'a> TryFrom<&'a str> for MountInfo {
+    type Error = io::Error;
+
+    fn try_from(line: &'a str) -> Result<Self, Self::Error> { line.parse::<Self>() }
+}
+
+impl MountInfo {
+    /// Attempt to parse a `/proc/mounts`-like line.
+    #[deprecated]
+    pub fn parse_line(line: &str) -> io::Result<MountInfo> { line.parse::<Self>() }
+
+    /// Parse a `/proc/mounts`-like line, also returning the fields as they were written.
+    pub fn parse_line_with_raw(line: &str) -> io::Result<(MountInfo, RawTokens)> {
+        let info = line.parse::<Self>()?;
+
+        // The line was parsed successfully, so each of these fields is present.
+        let mut parts = line.split_whitespace().map(String::from);
+        let mut next = || parts.next().unwrap_or_default();
+        let raw = RawTokens { source: next(), dest: next(), fstype: next(), options: next() };
+
+        Ok((info, raw))
+    }
+
+    /// Construct a `MountInfo` programmatically, beginning with its source, destination, and
+    /// file system type.
+    pub fn builder<S: Into<PathBuf>, D: Into<PathBuf>, F: Into<String>>(
+        source: S,
+        dest: D,
+        fstype: F,
+    ) -> MountInfoBuilder {
+        MountInfoBuilder::new(source, dest, fstype)
+    }
+
+    /// Parse a `/proc/mounts`-like line, reporting errors at the given `line_number`.
+    pub(crate) fn parse_numbered(line: &str, line_number: usize) -> Result<MountInfo, ParseError> {
+        MountInfoRef::parse_numbered(line, line_number)?.to_info()
+    }
+
+    /// Returns true if both mounts are equal, comparing their options as sets.
+    ///
+    /// The kernel may list the same options in a different order after a remount, which this
+    /// disregards. Every other field is compared exactly.
+    pub fn eq_ignoring_option_order(&self, other: &MountInfo) -> bool {
+        self.source == other.source
+            && self.dest == other.dest
+            && self.fstype == other.fstype
+            && self.dump == other.dump
+            && self.pass == other.pass
+            && self.options.iter().collect::<BTreeSet<_>>()
+                == other.options.iter().collect::<BTreeSet<_>>()
+    }
+
+    /// Returns true if this mount's destination is strictly beneath that of `other`.
+    ///
+    /// Paths are compared by their components, so `/mnt/data` is a submount of `/mnt`, but not
+    /// of `/m`, and no mount is a submount of a mount at the same destination.
+    pub fn is_submount_of(&self, other: &MountInfo) -> bool {
+        self.dest != other.dest && self.dest.starts_with(&other.dest)
+    }
+
+    /// Fetch the value of the first `key=value` option with the given `key`.
+    ///
+    /// Returns `None` if the key is absent, or is present as a bare flag without a value.
+    pub fn option_value(&self, key: &str) -> Option<&str> {
+        self.options.iter().find_map(|option| {
+            let mut parts = option.splitn(2, '=');
+            if parts.next() == Some(key) {
+                parts.next()
+            } else {
+                None
+            }
+        })
+    }
 
-        fn map_err(why: &'static str) -> io::Error { Error::new(ErrorKind::InvalidData, why) }
+    /// The options which configure systemd's handling of the mount, such as
+    /// `x-systemd.automount` or `x-systemd.idle-timeout=1min`, split into names and values.
+    ///
+    /// Names are given without their `x-systemd.` prefix, such as `automount`. The legacy
+    /// `comment=systemd.` form of these options is also accepted.
+    pub fn systemd_options(&self) -> Vec<(&str, Option<&str>)> {
+        const PREFIXES: &[&str] = &["x-systemd.", "comment=systemd."];
 
-        let source = parts.next().ok_or_else(|| map_err("missing source"))?;
-        let dest = parts.next().ok_or_else(|| map_err("missing dest"))?;
-        let fstype = parts.next().ok_or_else(|| map_err("missing type"))?;
-        let options = parts.next().ok_or_else(|| map_err("missing options"))?;
+        self.options
+            .iter()
+            .filter_map(|option| {
+                let prefix = PREFIXES.iter().find(|prefix| option.starts_with(*prefix))?;
+                let mut parts = option[prefix.len()..].splitn(2, '=');
+                Some((parts.next().unwrap_or(""), parts.next()))
+            })
+            .collect()
+    }
+
+    /// Returns true if the given `name` is present as a bare flag, such as `rw`.
+    pub fn has_flag(&self, name: &str) -> bool { self.options.iter().any(|option| option == name) }
+
+    /// The number of bytes given by the `size=` option, as used by `tmpfs` and `devtmpfs`.
+    ///
+    /// The value may have a `k`, `m`, `g`, or `t` suffix, which are binary multipliers, or no
+    /// suffix at all for bytes. Returns `None` if the option is absent, or if the size is given
+    /// as a percentage of memory, such as `size=50%`.
+    pub fn size_bytes(&self) -> Option<u64> {
+        let value = self.option_value("size")?;
+        let (number, multiplier) = match value.as_bytes().last()? {
+            b'k' | b'K' => (&value[..value.len() - 1], 1 << 10),
+            b'm' | b'M' => (&value[..value.len() - 1], 1 << 20),
+            b'g' | b'G' => (&value[..value.len() - 1], 1 << 30),
+            b't' | b'T' => (&value[..value.len() - 1], 1 << 40),
+            _ => (value, 1),
+        };
+
+        number.parse::<u64>().ok()?.checked_mul(multiplier)
+    }
+
+    /// The directories which make up an overlay mount, or `None` if this is not an overlay.
+    pub fn overlay_paths(&self) -> Option<OverlayPaths> {
+        if self.fstype == "overlay" {
+            Some(OverlayPaths::parse(self))
+        } else {
+            None
+        }
+    }
+
+    /// The remote host and path of an NFS or CIFS mount, or `None` for a local source.
+    pub fn network_source(&self) -> Option<NetworkSource> { NetworkSource::parse(self) }
+
+    /// The controllers attached to a cgroup v1 hierarchy, such as `cpu` and `cpuacct`.
+    ///
+    /// These are the options which are neither generic mount flags nor cgroup flags, such as
+    /// `xattr`, nor `key=value` options, such as `name=systemd`. Returns `None` if this is not a
+    /// `cgroup` mount.
+    pub fn cgroup_controllers(&self) -> Option<Vec<&str>> {
+        if self.fstype != "cgroup" {
+            return None;
+        }
+
+        let controllers = self
+            .options
+            .iter()
+            .map(String::as_str)
+            .filter(|option| !(option.contains('=') || NON_CONTROLLER_OPTIONS.contains(option)))
+            .collect();
+
+        Some(controllers)
+    }
 
-        let dump = parts.next().map_or(Ok(0), |value| {
-            value.parse::<i32>().map_err(|_| map_err("dump value is not a number"))
-        })?;
+    /// Returns true if this is a FUSE file system, with an fstype such as `fuse`, `fuseblk`, or
+    /// `fuse.sshfs`.
+    pub fn is_fuse(&self) -> bool {
+        self.fstype == "fuse" || self.fstype == "fuseblk" || self.fuse_subtype().is_some()
+    }
+
+    /// The driver of a FUSE file system, such as `sshfs` for a `fuse.sshfs` fstype.
+    ///
+    /// Returns `None` if this is not a FUSE file system, or if no subtype was given.
+    pub fn fuse_subtype(&self) -> Option<&str> {
+        ["fuse.", "fuseblk."].iter().find_map(|prefix| {
+            if self.fstype.starts_with(prefix) {
+                Some(&self.fstype[prefix.len()..])
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns true if the file system is mounted read-only.
+    ///
+    /// If both `ro` and `rw` are given, the last one wins.
+    pub fn is_read_only(&self) -> bool { self.last_flag("ro", "rw").unwrap_or(false) }
+
+    /// Returns true if execution of binaries is disallowed, via `noexec`.
+    ///
+    /// If both `noexec` and `exec` are given, the last one wins.
+    pub fn is_noexec(&self) -> bool { self.last_flag("noexec", "exec").unwrap_or(false) }
+
+    /// Returns true if set-user-ID and set-group-ID bits are ignored, via `nosuid`.
+    ///
+    /// If both `nosuid` and `suid` are given, the last one wins.
+    pub fn is_nosuid(&self) -> bool { self.last_flag("nosuid", "suid").unwrap_or(false) }
+
+    /// Returns true if device files are not interpreted, via `nodev`.
+    ///
+    /// If both `nodev` and `dev` are given, the last one wins.
+    pub fn is_nodev(&self) -> bool { self.last_flag("nodev", "dev").unwrap_or(false) }
 
-        let pass = parts.next().map_or(Ok(0), |value| {
-            value.parse::<i32>().map_err(|_| map_err("pass value is not a number"))
-        })?;
+    /// Returns true if the file system is mounted `noexec`, `nosuid`, and `nodev`, as is
+    /// expected of `/tmp`, `/var/tmp`, and `/dev/shm` on a hardened system.
+    pub fn is_hardened(&self) -> bool { self.is_noexec() && self.is_nosuid() && self.is_nodev() }
 
-        let path = Self::parse_value(source)?;
-        let path = path.to_str().ok_or_else(|| map_err("non-utf8 paths are unsupported"))?;
+    /// Parse the common options into a typed representation.
+    pub fn parsed_options(&self) -> MountOptions { MountOptions::parse(&self.options) }
 
-        let source = if path.starts_with("/dev/disk/by-") {
-            Self::fetch_from_disk_by_path(path)?
+    /// The options joined with commas, as they would be given to `mount -o`.
+    ///
+    /// Splitting the result on commas yields `options` again, unless an option itself contains
+    /// a comma.
+    pub fn options_string(&self) -> String { self.options.join(",") }
+
+    /// Returns true if the source is shaped like a path, such as `/dev/sda2`, rather than a
+    /// pseudo source, such as `proc` or `tmpfs`.
+    ///
+    /// This is a heuristic on the source's text alone, which is cheap, but which also accepts
+    /// paths that are not block devices, such as the directory of a bind mount. With the `libc`
+    /// feature, `source_is_block_device` checks the file itself.
+    pub fn has_block_source(&self) -> bool { self.source.starts_with("/") }
+
+    /// The name of the source's device file beneath `/dev`, such as `sda1` for `/dev/sda1`.
+    ///
+    /// Returns `None` if the source is not within `/dev`, such as `tmpfs`.
+    pub fn source_device_name(&self) -> Option<&OsStr> {
+        if self.source.starts_with("/dev") {
+            self.source.file_name()
         } else {
-            PathBuf::from(path)
+            None
+        }
+    }
+
+    /// The name of the disk which the source partition belongs to, such as `sda` for
+    /// `/dev/sda1`, or `nvme0n1` for `/dev/nvme0n1p2`.
+    ///
+    /// This is derived from the kernel's naming scheme alone. A device which is not a partition,
+    /// such as `/dev/nvme0n1` or `/dev/dm-0`, is returned as is. Returns `None` if the source is
+    /// not within `/dev`.
+    pub fn parent_disk_name(&self) -> Option<&OsStr> {
+        let name = self.source_device_name()?;
+        let bytes = name.as_bytes();
+
+        let stem = &bytes[..bytes.iter().rposition(|b| !b.is_ascii_digit()).map_or(0, |i| i + 1)];
+        if stem.len() == bytes.len() {
+            return Some(name);
+        }
+
+        // Disks whose names end with a digit separate their partition numbers with a `p`.
+        let separated =
+            stem.len() > 1 && stem.ends_with(b"p") && stem[stem.len() - 2].is_ascii_digit();
+
+        let disk = if separated {
+            &stem[..stem.len() - 1]
+        } else if stem.last().map_or(false, u8::is_ascii_lowercase)
+            && !DIGIT_SUFFIXED_DISKS.iter().any(|disk| stem.starts_with(disk.as_bytes()))
+        {
+            stem
+        } else {
+            bytes
+        };
+
+        Some(OsStr::from_bytes(disk))
+    }
+
+    /// The file which backs the source, if the source is a loop device such as `/dev/loop0`.
+    ///
+    /// This is read from `/sys/block/<loop>/loop/backing_file`. Returns `Ok(None)` if the source
+    /// is not a loop device, and an error of kind `NotFound` if the loop device has no backing
+    /// file in sysfs, such as when it has since been detached.
+    pub fn loop_backing_file(&self) -> io::Result<Option<PathBuf>> {
+        let name = match self.source_device_name().and_then(OsStr::to_str) {
+            Some(name) if is_loop_device(name) => name,
+            _ => return Ok(None),
+        };
+
+        let path = Path::new("/sys/block").join(name).join("loop/backing_file");
+        match fs::read(&path) {
+            Ok(mut contents) => {
+                if contents.last() == Some(&b'\n') {
+                    contents.pop();
+                }
+
+                Ok(Some(PathBuf::from(OsString::from_vec(contents))))
+            }
+            Err(ref why) if why.kind() == io::ErrorKind::NotFound => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} has no backing file at {}", self.source.display(), path.display()),
+            )),
+            Err(why) => Err(why),
+        }
+    }
+
+    /// Format the mount as a line of `/etc/fstab`, with the source given as `UUID=<uuid>`,
+    /// which remains stable across reboots, rather than a device path such as `/dev/sda2`.
+    ///
+    /// The UUID is found by searching `/dev/disk/by-uuid` for a link to the source device. If the
+    /// source is not within `/dev`, or has no UUID, the line is given with the source as is. An
+    /// error is returned if the source device does not exist.
+    ///
+    /// ```rust,no_run
+    /// extern crate proc_mounts;
+    ///
+    /// use proc_mounts::MountList;
+    /// use std::io;
+    ///
+    /// fn main() -> io::Result<()> {
+    ///     let mounts = MountList::new()?;
+    ///     if let Some(root) = mounts.get_mount_by_dest("/") {
+    ///         // Such as `UUID=0a1b2c3d-... / ext4 rw,relatime 0 0`
+    ///         println!("{}", root.to_fstab_line_with_uuid()?);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn to_fstab_line_with_uuid(&self) -> io::Result<String> {
+        let uuid = match self.source_uuid()? {
+            Some(uuid) => uuid,
+            None => return Ok(self.to_string()),
+        };
+
+        let mut info = self.clone();
+        info.source = PathBuf::from(format!("UUID={}", uuid.to_string_lossy()));
+        Ok(info.to_string())
+    }
+
+    /// Find the UUID of the source device through the links in `/dev/disk/by-uuid`.
+    fn source_uuid(&self) -> io::Result<Option<OsString>> {
+        if !self.source.starts_with("/dev") {
+            return Ok(None);
+        }
+
+        let device = fs::canonicalize(&self.source)?;
+        let entries = match fs::read_dir("/dev/disk/by-uuid") {
+            Ok(entries) => entries,
+            Err(ref why) if why.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(why) => return Err(why),
         };
 
-        let path = Self::parse_value(dest)?;
-        let path = path.to_str().ok_or_else(|| map_err("non-utf8 paths are unsupported"))?;
+        for entry in entries {
+            let entry = entry?;
+            if fs::canonicalize(entry.path()).ok().as_ref() == Some(&device) {
+                return Ok(Some(entry.file_name()));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns true if the source is backed by a removable block device.
+    ///
+    /// If the source is a partition, the flag of the disk which it belongs to is returned. An
+    /// error is returned if the source is not a block device, such as `tmpfs`.
+    pub fn is_removable(&self) -> io::Result<bool> {
+        let mut path = self.sys_block_path()?;
+        if path.join("partition").exists() {
+            path.pop();
+        }
 
-        let dest = PathBuf::from(path);
+        Ok(fs::read_to_string(path.join("removable"))?.trim() == "1")
+    }
+
+    /// Returns true if the source is a dm-crypt device, such as a LUKS volume opened at
+    /// `/dev/mapper/<name>`.
+    ///
+    /// The source is resolved to its device-mapper device, such as `dm-0`, and the device is
+    /// encrypted if its UUID in `/sys/block/dm-0/dm/uuid` has a `CRYPT-` prefix. This requires
+    /// read access to the device-mapper nodes of sysfs. Returns `Ok(false)` if the source is not
+    /// a device-mapper device, and an error if the source device does not exist.
+    pub fn is_encrypted(&self) -> io::Result<bool> {
+        if !self.source.starts_with("/dev") {
+            return Ok(false);
+        }
+
+        let device = fs::canonicalize(&self.source)?;
+        let is_dm = device
+            .file_name()
+            .and_then(OsStr::to_str)
+            .map_or(false, |name| name.starts_with("dm-"));
+
+        if !is_dm {
+            return Ok(false);
+        }
+
+        let uuid = fs::read_to_string(self.sys_block_path()?.join("dm/uuid"))?;
+        Ok(uuid.starts_with("CRYPT-"))
+    }
 
-        Ok(MountInfo {
-            source,
-            dest,
-            fstype: fstype.to_owned(),
-            options: options.split(',').map(String::from).collect(),
-            dump,
-            pass,
+    /// Resolve the source to its device directory in sysfs, such as
+    /// `/sys/devices/.../block/sda/sda1` for `/dev/sda1`.
+    fn sys_block_path(&self) -> io::Result<PathBuf> {
+        let not_block_device = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a block device", self.source.display()),
+            )
+        };
+
+        if !self.source.starts_with("/dev") {
+            return Err(not_block_device());
+        }
+
+        let device = fs::canonicalize(&self.source)?;
+        let name = device.file_name().ok_or_else(not_block_device)?;
+        fs::canonicalize(Path::new("/sys/class/block").join(name)).map_err(|why| {
+            if why.kind() == io::ErrorKind::NotFound {
+                not_block_device()
+            } else {
+                why
+            }
         })
     }
-}
 
-impl MountInfo {
-    /// Attempt to parse a `/proc/mounts`-like line.
-    #[deprecated]
-    pub fn parse_line(line: &str) -> io::Result<MountInfo> { line.parse::<Self>() }
+    /// Determine which of a flag or its negation was given last, as mount options are last-wins.
+    fn last_flag(&self, flag: &str, negation: &str) -> Option<bool> {
+        self.options.iter().rev().find_map(|option| {
+            if option == flag {
+                Some(true)
+            } else if option == negation {
+                Some(false)
+            } else {
+                None
+            }
+        })
+    }
 
-    fn fetch_from_disk_by_path(path: &str) -> io::Result<PathBuf> {
+    pub(crate) fn fetch_from_disk_by_path(
+        path: &str,
+        line_number: usize,
+    ) -> Result<PathBuf, ParseError> {
         PartitionID::from_disk_by_path(path)
-            .map_err(|why| Error::new(ErrorKind::InvalidData, format!("{}: {}", path, why)))?
+            .map_err(|why| ParseError::InvalidDevicePath {
+                path: path.to_owned(),
+                line_number,
+                reason: why.to_string(),
+            })?
             .get_device_path()
-            .ok_or_else(|| {
-                Error::new(ErrorKind::NotFound, format!("device path for {} was not found", path))
-            })
+            .ok_or_else(|| ParseError::DeviceNotFound { path: path.to_owned(), line_number })
     }
 
-    fn parse_value(value: &str) -> io::Result<OsString> {
-        let mut ret = Vec::new();
-
-        let mut bytes = value.bytes();
-        while let Some(b) = bytes.next() {
-            match b {
-                b'\\' => {
-                    let mut code = 0;
-                    for _i in 0..3 {
-                        if let Some(b) = bytes.next() {
-                            code *= 8;
-                            code += u32::from_str_radix(&(b as char).to_string(), 8)
-                                .map_err(|err| Error::new(ErrorKind::Other, err))?;
-                        } else {
-                            return Err(Error::new(ErrorKind::Other, "truncated octal code"));
-                        }
-                    }
-                    ret.push(code as u8);
-                }
-                _ => {
-                    ret.push(b);
-                }
+    /// Unescape each of the comma-separated `options`, decoding escapes through the `buffer`.
+    pub(crate) fn parse_options(
+        options: &str,
+        field: &'static str,
+        line_number: usize,
+        buffer: &mut Vec<u8>,
+    ) -> Result<Vec<String>, ParseError> {
+        options
+            .split(',')
+            .map(|option| Self::parse_string(option, field, line_number, buffer))
+            .collect()
+    }
+
+    /// Unescape a field which must be valid UTF-8, such as the fstype or an option.
+    ///
+    /// Escapes are decoded through the `buffer`, which may be reused across fields so that
+    /// each field only allocates its own `String`.
+    pub(crate) fn parse_string(
+        value: &str,
+        field: &'static str,
+        line_number: usize,
+        buffer: &mut Vec<u8>,
+    ) -> Result<String, ParseError> {
+        if !value.contains('\\') {
+            return Ok(value.to_owned());
+        }
+
+        Self::parse_value_into(value, line_number, buffer)?;
+        str::from_utf8(buffer)
+            .map(String::from)
+            .map_err(|_| ParseError::NonUtf8 { field, line_number })
+    }
+
+    pub(crate) fn parse_value(value: &str, line_number: usize) -> Result<OsString, ParseError> {
+        let mut buffer = Vec::new();
+        Self::parse_value_into(value, line_number, &mut buffer)?;
+        Ok(OsString::from_vec(buffer))
+    }
+
+    /// Decode the octal escapes of `value` into the `buffer`, replacing its contents.
+    pub(crate) fn parse_value_into(
+        value: &str,
+        line_number: usize,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), ParseError> {
+        let invalid = |sequence: &[u8]| ParseError::InvalidOctal {
+            line_number,
+            sequence: String::from_utf8_lossy(sequence).into_owned(),
+        };
+
+        buffer.clear();
+        buffer.reserve(value.len());
+
+        let bytes = value.as_bytes();
+        let mut pos = 0;
+        while pos < bytes.len() {
+            let b = bytes[pos];
+            pos += 1;
+
+            if b != b'\\' {
+                buffer.push(b);
+                continue;
+            }
+
+            let start = pos - 1;
+            let mut code = 0;
+            for _i in 0..3 {
+                let b = *bytes.get(pos).ok_or(ParseError::TruncatedOctal { line_number })?;
+                pos += 1;
+                let digit = (b as char).to_digit(8).ok_or_else(|| invalid(&bytes[start..pos]))?;
+                code = code * 8 + digit;
+            }
+
+            if code > 0o377 {
+                return Err(invalid(&bytes[start..pos]));
             }
+
+            buffer.push(code as u8);
         }
 
-        Ok(OsString::from_vec(ret))
+        Ok(())
     }
 }
@@ -3,13 +3,13 @@ use std::{
     fs::File,
     io::{self, BufRead, BufReader},
     path::Path,
-    str::FromStr,
 };
 
 /// Iteratively parse the `/proc/mounts` file.
 pub struct MountIter<R> {
-    file:   R,
-    buffer: String,
+    file:        R,
+    buffer:      String,
+    line_number: usize,
 }
 
 impl MountIter<BufReader<File>> {
@@ -24,7 +24,7 @@ impl MountIter<BufReader<File>> {
 impl<R: BufRead> MountIter<R> {
     /// Read mounts from any in-memory buffer.
     pub fn new_from_reader(readable: R) -> Self {
-        Self { file: readable, buffer: String::with_capacity(512) }
+        Self { file: readable, buffer: String::with_capacity(512), line_number: 0 }
     }
 
     /// Iterator-based variant of `source_mounted_at`.
@@ -63,9 +63,13 @@ impl<R: BufRead> Iterator for MountIter<R> {
             match self.file.read_line(&mut self.buffer) {
                 Ok(read) if read == 0 => return None,
                 Ok(_) => {
+                    self.line_number += 1;
                     let line = self.buffer.trim_start();
                     if !(line.starts_with('#') || line.is_empty()) {
-                        return Some(MountInfo::from_str(line));
+                        return Some(
+                            MountInfo::parse_numbered(line, self.line_number)
+                                .map_err(io::Error::from),
+                        );
                     }
                 }
                 Err(why) => return Some(Err(why)),
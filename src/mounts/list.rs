@@ -1,24 +1,238 @@
-use super::{MountInfo, MountIter};
+use super::{
+    timeout::with_timeout, MountDiff, MountInfo, MountInfoRef, MountIter, MountNode, Reconciliation,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::{
-    io::{self, BufRead},
-    os::unix::ffi::OsStrExt,
-    path::Path,
-    str::FromStr,
+    cmp::Reverse,
+    collections::{BTreeSet, HashSet},
+    env,
+    ffi::OsStr,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Read, Write},
+    iter::FromIterator,
+    mem::ManuallyDrop,
+    ops::Deref,
+    os::unix::{
+        ffi::OsStrExt,
+        io::{FromRawFd, RawFd},
+    },
+    path::{Path, PathBuf},
+    slice,
+    time::Duration,
+    vec,
 };
+use ParseError;
+
+/// File system types which are not backed by storage, and are excluded by
+/// `MountList::real_filesystems`.
+pub const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "autofs",
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devpts",
+    "devtmpfs",
+    "efivarfs",
+    "fusectl",
+    "hugetlbfs",
+    "mqueue",
+    "nsfs",
+    "proc",
+    "pstore",
+    "ramfs",
+    "rpc_pipefs",
+    "securityfs",
+    "sysfs",
+    "tmpfs",
+    "tracefs",
+];
 
 /// A list of parsed mount entries from `/proc/mounts`.
+///
+/// The list dereferences to a slice of its entries, and may be iterated directly.
+///
+/// ```rust
+/// extern crate proc_mounts;
+///
+/// use proc_mounts::MountList;
+///
+/// let list = MountList::parse_from(
+///     "/dev/sda2 / ext4 rw 0 0\n/dev/sda1 /boot/efi vfat rw 0 0".lines(),
+/// ).unwrap();
+///
+/// assert_eq!(list.len(), 2);
+/// assert_eq!(list.first().unwrap().fstype, "ext4");
+///
+/// for mount in &list {
+///     println!("{} is mounted at {}", mount.source.display(), mount.dest.display());
+/// }
+/// ```
 #[derive(Debug, Default, Clone, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MountList(pub Vec<MountInfo>);
 
 impl MountList {
+    /// Lazily parse mounts given from an iterator of mount entry lines.
+    ///
+    /// Each line is parsed only when requested, so callers may stop early, or skip lines which
+    /// fail to parse. Lines which are empty or contain only whitespace are skipped.
+    pub fn iter_from<'a, I: Iterator<Item = &'a str>>(
+        lines: I,
+    ) -> impl Iterator<Item = io::Result<MountInfo>> {
+        fn parse((id, line): (usize, &str)) -> io::Result<MountInfo> {
+            MountInfo::parse_numbered(line, id + 1).map_err(io::Error::from)
+        }
+
+        lines.enumerate().filter(is_not_blank).map(parse)
+    }
+
+    /// Lazily parse mounts from the `contents` of a mount tab, borrowing each field from it.
+    ///
+    /// As with `iter_from`, only blank lines are skipped.
+    pub fn iter_refs(contents: &str) -> impl Iterator<Item = io::Result<MountInfoRef>> {
+        fn parse((id, line): (usize, &str)) -> io::Result<MountInfoRef> {
+            MountInfoRef::parse_numbered(line, id + 1).map_err(io::Error::from)
+        }
+
+        contents.lines().enumerate().filter(is_not_blank).map(parse)
+    }
+
     /// Parse mounts given from an iterator of mount entry lines.
+    ///
+    /// Lines which are empty or contain only whitespace are skipped, as may be seen while
+    /// `/proc/mounts` is being repopulated.
     pub fn parse_from<'a, I: Iterator<Item = &'a str>>(lines: I) -> io::Result<MountList> {
-        lines.map(MountInfo::from_str).collect::<io::Result<Vec<MountInfo>>>().map(MountList)
+        Self::iter_from(lines).collect::<io::Result<Vec<MountInfo>>>().map(MountList)
     }
 
+    /// Parse mounts given from an iterator of `/etc/fstab` lines.
+    ///
+    /// Blank lines, and lines whose first non-whitespace character is `#`, are skipped. Line
+    /// numbers in errors still count the skipped lines.
+    pub fn parse_fstab<'a, I: Iterator<Item = &'a str>>(lines: I) -> io::Result<MountList> {
+        lines
+            .enumerate()
+            .filter(is_entry)
+            .map(|(id, line)| MountInfo::parse_numbered(line, id + 1))
+            .collect::<Result<Vec<MountInfo>, ParseError>>()
+            .map(MountList)
+            .map_err(io::Error::from)
+    }
+
+    /// Parse mounts given from an iterator of mount entry lines, skipping lines which fail to
+    /// parse.
+    ///
+    /// The errors for skipped lines are returned alongside their line numbers, which start at 1.
+    /// As with `parse_from`, lines which are empty or contain only whitespace are skipped
+    /// without an error.
+    pub fn parse_lenient<'a, I: Iterator<Item = &'a str>>(
+        lines: I,
+    ) -> (MountList, Vec<(usize, ParseError)>) {
+        let mut mounts = Vec::new();
+        let mut errors = Vec::new();
+
+        for (id, line) in lines.enumerate().filter(is_not_blank) {
+            match MountInfo::parse_numbered(line, id + 1) {
+                Ok(mount) => mounts.push(mount),
+                Err(why) => errors.push((id + 1, why)),
+            }
+        }
+
+        (MountList(mounts), errors)
+    }
+
+    /// Create a list from mount entries which are already in memory, such as those built with
+    /// `MountInfo::builder` to mock the system's mounts in tests.
+    ///
+    /// ```rust
+    /// extern crate proc_mounts;
+    ///
+    /// use proc_mounts::{MountInfo, MountList};
+    ///
+    /// let list = MountList::from_entries(vec![
+    ///     MountInfo::builder("/dev/sda2", "/", "ext4").option("rw").build(),
+    ///     MountInfo::builder("/dev/sda1", "/boot/efi", "vfat").option("rw").build(),
+    /// ]);
+    ///
+    /// assert_eq!(list.len(), 2);
+    /// assert!(list.is_mount_point("/boot/efi"));
+    /// ```
+    pub fn from_entries(entries: Vec<MountInfo>) -> MountList { MountList(entries) }
+
     /// Read a new list of mounts into memory from `/proc/mounts`.
-    pub fn new() -> io::Result<MountList> {
-        Ok(MountList(MountIter::new()?.collect::<io::Result<Vec<MountInfo>>>()?))
+    pub fn new() -> io::Result<MountList> { Self::from_file("/proc/mounts") }
+
+    /// Read a new list of mounts from `/proc/mounts`, as with `new`, reading the file into the
+    /// given buffer.
+    ///
+    /// The buffer is cleared first, and retains its capacity afterwards, so that a caller which
+    /// rereads the mounts frequently may reuse it rather than allocating anew on each read.
+    pub fn refresh_into(buf: &mut String) -> io::Result<MountList> {
+        buf.clear();
+        File::open("/proc/mounts")?.read_to_string(buf)?;
+        Self::parse_from(buf.lines())
+    }
+
+    /// Read a new list of mounts into memory from `/proc/mounts`, with each source and
+    /// destination resolved to its canonical path.
+    ///
+    /// Unlike `new`, this performs a filesystem lookup for every entry. See `canonicalize`.
+    pub fn new_canonicalized() -> io::Result<MountList> {
+        let mut mounts = Self::new()?;
+        mounts.canonicalize();
+        Ok(mounts)
+    }
+
+    /// Read a new list of mounts as with `new_canonicalized`, giving up with an error of kind
+    /// `TimedOut` if they are not all canonicalized within `timeout`.
+    ///
+    /// Canonicalizing a path on a network file system whose server is unreachable may block
+    /// indefinitely. Each call spawns a thread to read and canonicalize the mounts. On a
+    /// timeout, that thread stays blocked, and so one thread is leaked for as long as the
+    /// server is unreachable.
+    pub fn new_canonicalized_timeout(timeout: Duration) -> io::Result<MountList> {
+        with_timeout(timeout, Self::new_canonicalized)
+    }
+
+    /// Read a new list of mounts into memory from `/etc/mtab`.
+    ///
+    /// On most systems, `/etc/mtab` is a symlink to `/proc/mounts`. Where it is instead a
+    /// regular file maintained by `mount(8)`, it may be stale. If `/etc/mtab` does not exist,
+    /// use `new` to read the mounts from the kernel.
+    pub fn from_mtab() -> io::Result<MountList> { Self::from_file("/etc/mtab") }
+
+    /// Read a new list of mounts into memory from the mount namespace of the process with the
+    /// given `pid`, from `/proc/<pid>/mounts`.
+    ///
+    /// An error is returned if the process does not exist, or if its mounts may not be read.
+    pub fn from_pid(pid: u32) -> io::Result<MountList> {
+        Self::from_file(format!("/proc/{}/mounts", pid))
+    }
+
+    /// Read a new list of mounts into memory from the given path, such as
+    /// `/proc/<pid>/mounts` for another process's mount namespace.
+    ///
+    /// This is equivalent to `new_from_file`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<MountList> { Self::new_from_file(path) }
+
+    /// Read a new list of mounts into memory from an open file descriptor of a mount-tab-like
+    /// file, such as `/proc/<pid>/mounts` opened with `openat` in another namespace.
+    ///
+    /// The descriptor is borrowed: it is read from its current offset, and is left open for the
+    /// caller to close.
+    ///
+    /// # Safety
+    ///
+    /// `fd` must be an open file descriptor which the caller owns, or otherwise may read from,
+    /// and it must remain open until this function returns.
+    pub unsafe fn from_fd(fd: RawFd) -> io::Result<MountList> {
+        // The caller retains ownership, so the file must not close the descriptor when dropped.
+        let file = ManuallyDrop::new(File::from_raw_fd(fd));
+        Self::new_from_reader(BufReader::new(&*file))
     }
 
     /// Read a new list of mounts into memory from any mount-tab-like file.
@@ -31,6 +245,65 @@ impl MountList {
         Ok(MountList(MountIter::new_from_reader(reader).collect::<io::Result<Vec<MountInfo>>>()?))
     }
 
+    /// Parse mounts from the contents of a mount-tab-like file, consuming the contents.
+    ///
+    /// The returned list owns all of its data, so the contents are dropped once parsed.
+    pub fn parse_owned(contents: String) -> io::Result<MountList> {
+        Self::new_from_reader(contents.as_bytes())
+    }
+
+    /// Read a new list of mounts into memory from any unbuffered source, such as a fixture
+    /// or a captured snapshot of `/proc/mounts`.
+    pub fn parse_reader<R: Read>(reader: R) -> io::Result<MountList> {
+        Self::new_from_reader(BufReader::new(reader))
+    }
+
+    /// Resolve symlinks in each source and destination, such as `/dev/mapper/root` to
+    /// `/dev/dm-0`.
+    ///
+    /// Paths which are not absolute, such as the `proc` or `tmpfs` pseudo sources, or which
+    /// cannot be canonicalized, are left as they are.
+    pub fn canonicalize(&mut self) {
+        fn canonicalize(path: &mut PathBuf) {
+            if path.is_absolute() {
+                if let Ok(canonical) = fs::canonicalize(&path) {
+                    *path = canonical;
+                }
+            }
+        }
+
+        for mount in &mut self.0 {
+            canonicalize(&mut mount.source);
+            canonicalize(&mut mount.dest);
+        }
+    }
+
+    /// The number of mounts in the list.
+    #[must_use]
+    pub fn len(&self) -> usize { self.0.len() }
+
+    /// Returns true if the list contains no mounts.
+    #[must_use]
+    pub fn is_empty(&self) -> bool { self.0.is_empty() }
+
+    /// A copy of the list, sorted by destination. See `sort_by_dest`.
+    pub fn sorted_by_dest(&self) -> MountList {
+        let mut mounts = self.clone();
+        mounts.sort_by_dest();
+        mounts
+    }
+
+    /// Sort the list by destination, comparing paths by their components.
+    ///
+    /// The sort is stable, so mounts which share a destination keep their order.
+    pub fn sort_by_dest(&mut self) { self.0.sort_by(|a, b| a.dest.cmp(&b.dest)) }
+
+    /// A copy of the list with exact duplicate entries removed, keeping the first of each.
+    pub fn dedup(&self) -> MountList {
+        let mut seen = HashSet::new();
+        MountList(self.0.iter().filter(|mount| seen.insert(*mount)).cloned().collect())
+    }
+
     // Returns true if the `source` is mounted at the given `dest`.
     pub fn source_mounted_at<D: AsRef<Path>, P: AsRef<Path>>(&self, source: D, path: P) -> bool {
         self.get_mount_by_source(source)
@@ -38,32 +311,303 @@ impl MountList {
     }
 
     /// Find the first mount which which has the `path` destination.
+    ///
+    /// Paths are compared by their components, so a trailing slash in `path`, as is common in
+    /// user input, still matches. The comparison is case-sensitive.
     pub fn get_mount_by_dest<P: AsRef<Path>>(&self, path: P) -> Option<&MountInfo> {
-        self.0.iter().find(|mount| mount.dest == path.as_ref())
+        self.mounts_by_dest(path).next()
+    }
+
+    /// Find the first mount which has the `path` destination, after stripping any trailing
+    /// slashes from `path`, as is common in user input. The root path, `/`, is kept as is.
+    ///
+    /// The comparison is case-sensitive, so `/Mnt/Data/` does not match `/mnt/data`.
+    pub fn get_mount_by_dest_normalized<P: AsRef<Path>>(&self, path: P) -> Option<&MountInfo> {
+        let bytes = path.as_ref().as_os_str().as_bytes();
+        let end = bytes.iter().rposition(|&b| b != b'/').map_or(bytes.len().min(1), |i| i + 1);
+        self.get_mount_by_dest(OsStr::from_bytes(&bytes[..end]))
+    }
+
+    /// Find the last mount which has the `path` destination, which is the one visible there.
+    ///
+    /// Where a destination is mounted over, each mount is listed in the order mounted, and the
+    /// latest hides those before it. `get_mount_by_dest` instead returns the earliest, which may
+    /// be hidden.
+    pub fn effective_mount_by_dest<P: AsRef<Path>>(&self, path: P) -> Option<&MountInfo> {
+        self.mounts_by_dest(path).last()
+    }
+
+    /// Returns true if a file system is mounted at the `path` destination.
+    pub fn is_mount_point<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.0.iter().any(|mount| mount.dest == path.as_ref())
     }
 
-    /// Find the first mount hich has the source `path`.
+    /// Iterate through each mount which has the `path` destination, in the order listed.
+    pub fn mounts_by_dest<P: AsRef<Path>>(&self, path: P) -> impl Iterator<Item = &MountInfo> {
+        self.0.iter().filter(move |mount| mount.dest == path.as_ref())
+    }
+
+    /// Find the first mount which has the source `path`.
+    ///
+    /// A source may be mounted in multiple places. See `mounts_by_source` to find all of them.
     pub fn get_mount_by_source<P: AsRef<Path>>(&self, path: P) -> Option<&MountInfo> {
-        self.0.iter().find(|mount| mount.source == path.as_ref())
+        self.mounts_by_source(path).next()
+    }
+
+    /// Returns true if any mount has the `path` destination.
+    ///
+    /// This is an alias of `is_mount_point`, for symmetry with `contains_source`.
+    pub fn contains_dest<P: AsRef<Path>>(&self, path: P) -> bool { self.is_mount_point(path) }
+
+    /// Returns true if any mount has the source `path`.
+    pub fn contains_source<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.get_mount_by_source(path).is_some()
+    }
+
+    /// Iterate through each mount which has the source `path`, in the order listed.
+    ///
+    /// This is common for bind mounts, and for btrfs devices with multiple subvolumes mounted.
+    pub fn mounts_by_source<P: AsRef<Path>>(&self, path: P) -> impl Iterator<Item = &MountInfo> {
+        self.0.iter().filter(move |mount| mount.source == path.as_ref())
+    }
+
+    /// The number of times that the `source` is mounted, such as to assert that the root
+    /// device is mounted exactly once.
+    pub fn source_mount_count<P: AsRef<Path>>(&self, source: P) -> usize {
+        self.mounts_by_source(source).count()
+    }
+
+    /// Find the mount of the top-level subvolume of a btrfs `source`.
+    ///
+    /// This is the mount whose `subvol` option is `/`, or whose `subvolid` option is `5`. Other
+    /// file systems do not have subvolumes, so `None` is returned for them.
+    pub fn root_subvolume_mount<P: AsRef<Path>>(&self, source: P) -> Option<&MountInfo> {
+        self.mounts_by_source(source).find(|mount| {
+            mount.option_value("subvol") == Some("/") || mount.option_value("subvolid") == Some("5")
+        })
+    }
+
+    /// Find the mount which the given `path` resides on.
+    ///
+    /// This is the mount whose destination is the longest component-wise prefix of `path`. If
+    /// multiple mounts share that destination, the last one is returned, as it is the one which
+    /// is visible. The `path` should be absolute and canonicalized, as no filesystem lookups are
+    /// performed.
+    pub fn mount_for_path<P: AsRef<Path>>(&self, path: P) -> Option<&MountInfo> {
+        let path = path.as_ref();
+        self.0
+            .iter()
+            .filter(|mount| path.starts_with(&mount.dest))
+            .max_by_key(|mount| mount.dest.components().count())
+    }
+
+    /// Returns true if the root file system is mounted read-only, or `None` if no file system is
+    /// mounted at `/`.
+    ///
+    /// If `/` is mounted more than once, the last mount is used, as it is the one which is
+    /// visible.
+    pub fn root_is_read_only(&self) -> Option<bool> {
+        self.mounts_by_dest("/").last().map(MountInfo::is_read_only)
+    }
+
+    /// Find the mount which the current working directory resides on, as with `mount_for_path`.
+    pub fn mount_for_cwd(&self) -> io::Result<Option<&MountInfo>> {
+        env::current_dir().map(|cwd| self.mount_for_path(cwd))
+    }
+
+    /// Iterate through each mount which has the given file system type.
+    pub fn with_fstype<'a>(&'a self, fstype: &'a str) -> impl Iterator<Item = &'a MountInfo> {
+        self.0.iter().filter(move |mount| mount.fstype == fstype)
+    }
+
+    /// Returns true if a file system of the given type is mounted anywhere, such as to check
+    /// whether `cgroup2` is available.
+    pub fn has_fstype(&self, fstype: &str) -> bool { self.first_with_fstype(fstype).is_some() }
+
+    /// Find the first mount which has the given file system type.
+    pub fn first_with_fstype(&self, fstype: &str) -> Option<&MountInfo> {
+        self.0.iter().find(|mount| mount.fstype == fstype)
+    }
+
+    /// The distinct file system types of every mount, in sorted order.
+    pub fn fstypes(&self) -> BTreeSet<String> {
+        self.0.iter().map(|mount| mount.fstype.clone()).collect()
+    }
+
+    /// Iterate through each mount whose file system type is not in `PSEUDO_FILESYSTEMS`.
+    pub fn real_filesystems(&self) -> impl Iterator<Item = &MountInfo> {
+        self.filter_fstypes(|fstype| !PSEUDO_FILESYSTEMS.contains(&fstype))
+    }
+
+    /// Iterate through each mount whose file system type is accepted by the `predicate`.
+    ///
+    /// This may be used to adjust the set of file systems which `real_filesystems` excludes,
+    /// such as to include `tmpfs`.
+    pub fn filter_fstypes<'a>(
+        &'a self,
+        predicate: impl Fn(&str) -> bool + 'a,
+    ) -> impl Iterator<Item = &'a MountInfo> {
+        self.0.iter().filter(move |mount| predicate(&mount.fstype))
+    }
+
+    /// Write each mount to the `writer` in the format of `/proc/mounts`, one per line.
+    ///
+    /// The output may be parsed back into an identical list with `parse_reader`.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for mount in &self.0 {
+            writeln!(writer, "{}", mount)?;
+        }
+
+        Ok(())
+    }
+
+    /// Format each mount as a line of `/proc/mounts`, as written by `write_to`.
+    pub fn to_mounts_string(&self) -> String {
+        let mut string = String::new();
+        for mount in &self.0 {
+            string.push_str(&mount.to_string());
+            string.push('\n');
+        }
+
+        string
+    }
+
+    /// Build the hierarchy of mounts, where each mount is nested beneath the mount with the
+    /// longest destination that contains it. The root of the tree is `/`.
+    pub fn tree(&self) -> MountNode { MountNode::new(self) }
+
+    /// Iterate through each mount alongside its direct children, as they are nested in `tree`.
+    ///
+    /// Mounts are given depth-first from `/`, with each parent before its children, so that a
+    /// renderer may indent them without walking the `MountNode` hierarchy itself.
+    pub fn iter_with_children(&self) -> impl Iterator<Item = (&MountInfo, Vec<&MountInfo>)> {
+        type Pairs<'a> = Vec<(&'a MountInfo, Vec<&'a MountInfo>)>;
+
+        fn flatten<'a>(node: &MountNode<'a>, pairs: &mut Pairs<'a>) {
+            if let Some(mount) = node.mount {
+                pairs.push((mount, node.children.iter().filter_map(|child| child.mount).collect()));
+            }
+
+            for child in &node.children {
+                flatten(child, pairs);
+            }
+        }
+
+        let mut pairs = Vec::new();
+        flatten(&self.tree(), &mut pairs);
+        pairs.into_iter()
+    }
+
+    /// Returns true if both lists have the same mounts in the same order, disregarding options
+    /// for which `ignore_option` returns true.
+    ///
+    /// Each pair of mounts is compared by source, destination, file system type, and the
+    /// options which are not ignored, in order. This allows volatile options, such as the
+    /// `size=` of a `tmpfs`, to be disregarded when comparing captures of `/proc/mounts`.
+    pub fn eq_ignoring_options<F: Fn(&str) -> bool>(
+        &self,
+        other: &MountList,
+        ignore_option: F,
+    ) -> bool {
+        let options = |mount: &MountInfo| -> Vec<String> {
+            mount.options.iter().filter(|option| !ignore_option(option)).cloned().collect()
+        };
+
+        self.0.len() == other.0.len()
+            && self.0.iter().zip(&other.0).all(|(a, b)| {
+                a.source == b.source
+                    && a.dest == b.dest
+                    && a.fstype == b.fstype
+                    && options(a) == options(b)
+            })
+    }
+
+    /// Compare this list of mounts to a newer list, matching mounts by their destination.
+    pub fn diff<'a>(&'a self, other: &'a MountList) -> MountDiff<'a> { MountDiff::new(self, other) }
+
+    /// Compare these live mounts to the entries of an fstab, such as from `parse_fstab`, to
+    /// find which entries are mounted, which are missing, and which mounts are not listed.
+    pub fn reconcile<'a>(&'a self, fstab: &'a MountList) -> Reconciliation<'a> {
+        Reconciliation::new(self, fstab)
     }
 
-    /// Iterate through each source that starts with the given `path`.
+    /// Every mount whose destination is beneath the given `path`, deepest first.
+    ///
+    /// Paths are compared by their components, so `/mnt/dat` does not contain `/mnt/data`, and
+    /// a mount at `path` itself is excluded. Mounts of equal depth are given in the reverse of
+    /// the order listed, so that the order may be followed to unmount children before parents.
+    pub fn mounts_under<'a>(&'a self, path: &'a Path) -> Vec<&'a MountInfo> {
+        deepest_first(
+            self.0.iter().filter(|mount| mount.dest != path && mount.dest.starts_with(path)),
+        )
+    }
+
+    /// Every mount, in the order which they may be unmounted.
+    ///
+    /// Deeper destinations come first, and mounts of equal depth are given in the reverse of
+    /// the order listed, as the most recent mount at a destination is the one visible.
+    pub fn unmount_order(&self) -> Vec<&MountInfo> { deepest_first(self.0.iter()) }
+
+    /// Iterate through each mount in the reverse of the order listed, from the most recently
+    /// mounted to the least.
+    ///
+    /// Unmounting in this order removes each mount before any which it was mounted on top of.
+    /// See `unmount_order` to order by destination depth instead.
+    ///
+    /// ```rust
+    /// extern crate proc_mounts;
+    ///
+    /// use proc_mounts::MountList;
+    /// use std::path::Path;
+    ///
+    /// let list = MountList::parse_from(
+    ///     "/dev/sda2 / ext4 rw 0 0\n/dev/sda1 /boot/efi vfat rw 0 0".lines(),
+    /// ).unwrap();
+    ///
+    /// let dests = list.iter_rev().map(|mount| mount.dest.as_path()).collect::<Vec<_>>();
+    /// assert_eq!(dests, vec![Path::new("/boot/efi"), Path::new("/")]);
+    /// ```
+    pub fn iter_rev(&self) -> impl Iterator<Item = &MountInfo> { self.0.iter().rev() }
+
+    /// Iterate through each source that is, or is beneath, the given `path`.
+    ///
+    /// Paths are compared by their components, so `/dev/sd` does not match `/dev/sda1`. See
+    /// `source_starts_with_bytes` to match a partial component.
     pub fn source_starts_with<'a>(
         &'a self,
         path: &'a Path,
     ) -> Box<dyn Iterator<Item = &MountInfo> + 'a> {
-        self.starts_with(path.as_os_str().as_bytes(), |m| &m.source)
+        Box::new(self.0.iter().filter(move |mount| mount.source.starts_with(path)))
     }
 
-    /// Iterate through each destination that starts with the given `path`.
+    /// Iterate through each destination that is, or is beneath, the given `path`.
+    ///
+    /// Paths are compared by their components, so `/boot` does not match `/bootmgr`. See
+    /// `destination_starts_with_bytes` to match a partial component.
     pub fn destination_starts_with<'a>(
         &'a self,
         path: &'a Path,
     ) -> Box<dyn Iterator<Item = &MountInfo> + 'a> {
-        self.starts_with(path.as_os_str().as_bytes(), |m| &m.dest)
+        Box::new(self.0.iter().filter(move |mount| mount.dest.starts_with(path)))
+    }
+
+    /// Iterate through each source whose bytes begin with those of the given `path`.
+    pub fn source_starts_with_bytes<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Box<dyn Iterator<Item = &MountInfo> + 'a> {
+        self.starts_with_bytes(path.as_os_str().as_bytes(), |m| &m.source)
+    }
+
+    /// Iterate through each destination whose bytes begin with those of the given `path`.
+    pub fn destination_starts_with_bytes<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Box<dyn Iterator<Item = &MountInfo> + 'a> {
+        self.starts_with_bytes(path.as_os_str().as_bytes(), |m| &m.dest)
     }
 
-    fn starts_with<'a, F: Fn(&'a MountInfo) -> &'a Path + 'a>(
+    fn starts_with_bytes<'a, F: Fn(&'a MountInfo) -> &'a Path + 'a>(
         &'a self,
         path: &'a [u8],
         func: F,
@@ -76,3 +620,47 @@ impl MountList {
         Box::new(iterator)
     }
 }
+
+/// Sort mounts by descending destination depth, with later mounts first among equal depths.
+fn deepest_first<'a, I: DoubleEndedIterator<Item = &'a MountInfo>>(
+    mounts: I,
+) -> Vec<&'a MountInfo> {
+    let mut mounts = mounts.rev().collect::<Vec<_>>();
+    mounts.sort_by_key(|mount| Reverse(mount.dest.components().count()));
+    mounts
+}
+
+/// Returns true if the line contains anything other than whitespace.
+fn is_not_blank(&(_, line): &(usize, &str)) -> bool { !line.trim().is_empty() }
+
+/// Returns true if the line is neither blank nor a comment.
+fn is_entry(&(_, line): &(usize, &str)) -> bool {
+    let line = line.trim_start();
+    !(line.starts_with('#') || line.is_empty())
+}
+
+impl Deref for MountList {
+    type Target = [MountInfo];
+
+    fn deref(&self) -> &Self::Target { &self.0 }
+}
+
+impl FromIterator<MountInfo> for MountList {
+    fn from_iter<I: IntoIterator<Item = MountInfo>>(iter: I) -> Self {
+        MountList(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for MountList {
+    type IntoIter = vec::IntoIter<MountInfo>;
+    type Item = MountInfo;
+
+    fn into_iter(self) -> Self::IntoIter { self.0.into_iter() }
+}
+
+impl<'a> IntoIterator for &'a MountList {
+    type IntoIter = slice::Iter<'a, MountInfo>;
+    type Item = &'a MountInfo;
+
+    fn into_iter(self) -> Self::IntoIter { self.0.iter() }
+}
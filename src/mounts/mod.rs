@@ -1,14 +1,50 @@
+mod borrowed;
+mod builder;
+mod cache;
+#[cfg(feature = "libc")]
+mod device;
+mod diff;
 mod info;
 mod iter;
 mod list;
+mod mountinfo;
+mod network;
+mod octal;
+mod options;
+mod overlay;
+mod reconcile;
 mod tab;
+mod timeout;
+mod tree;
+#[cfg(feature = "libc")]
+mod usage;
+#[cfg(feature = "poll")]
+mod watcher;
 
-pub use self::{info::*, iter::*, list::*, tab::*};
+#[cfg(feature = "libc")]
+pub use self::usage::*;
+#[cfg(feature = "poll")]
+pub use self::watcher::*;
+pub use self::{
+    borrowed::*, builder::*, cache::*, diff::*, info::*, iter::*, list::*, mountinfo::*,
+    network::*, octal::*, options::*, overlay::*, reconcile::*, tab::*, tree::*,
+};
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::{Path, PathBuf};
+    use std::{
+        borrow::Cow,
+        collections::HashSet,
+        env,
+        ffi::{OsStr, OsString},
+        fs,
+        io::{self, Read, Seek, SeekFrom},
+        os::unix::{ffi::OsStrExt, fs::symlink, io::AsRawFd},
+        path::{Path, PathBuf},
+        process,
+    };
+    use ParseError;
 
     const SAMPLE: &str = r#"sysfs /sys sysfs rw,nosuid,nodev,noexec,relatime 0 0
 proc /proc proc rw,nosuid,nodev,noexec,relatime 0 0
@@ -26,6 +62,1350 @@ fusectl /sys/fs/fuse/connections fusectl rw,relatime 0 0
         assert!(mounts.source_mounted_at("/dev/sda1", "/boot/efi"));
     }
 
+    #[test]
+    fn mount_for_path() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let dest_for = |path: &str| mounts.mount_for_path(path).map(|m| m.dest.clone());
+
+        assert_eq!(dest_for("/mnt/data/projects/foo"), Some(PathBuf::from("/mnt/data")));
+        assert_eq!(dest_for("/mnt/data"), Some(PathBuf::from("/mnt/data")));
+        assert_eq!(dest_for("/mnt/database"), Some(PathBuf::from("/")));
+        assert_eq!(dest_for("/boot/efi/EFI"), Some(PathBuf::from("/boot/efi")));
+        assert_eq!(dest_for("/home/user"), Some(PathBuf::from("/")));
+        assert_eq!(dest_for("relative"), None);
+
+        let overmounted = MountList::parse_from(
+            "/dev/sda2 / ext4 rw 0 0\n/dev/sdb1 /mnt ext4 rw 0 0\n/dev/sdc1 /mnt xfs rw 0 0"
+                .lines(),
+        )
+        .unwrap();
+        assert_eq!(overmounted.mount_for_path("/mnt/file").unwrap().fstype, "xfs");
+    }
+
+    #[test]
+    fn mount_for_cwd() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let cwd = env::current_dir().unwrap();
+        assert_eq!(mounts.mount_for_cwd().unwrap(), mounts.mount_for_path(&cwd));
+        assert!(mounts.mount_for_cwd().unwrap().is_some());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let json = serde_json::to_string(&mounts).unwrap();
+        assert_eq!(serde_json::from_str::<MountList>(&json).unwrap(), mounts);
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let info = MountInfo {
+            source:  PathBuf::from("/dev/sdb1"),
+            dest:    PathBuf::from("/media/user/My Disk\\\tBackup"),
+            fstype:  "ext4".into(),
+            options: vec!["rw".into(), "nosuid".into()],
+            dump:    0,
+            pass:    2,
+        };
+
+        let line = info.to_string();
+        assert_eq!(line, "/dev/sdb1 /media/user/My\\040Disk\\134\\011Backup ext4 rw,nosuid 0 2");
+        assert_eq!(line.parse::<MountInfo>().unwrap(), info);
+    }
+
+    #[test]
+    fn write_to() {
+        let mut mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        mounts
+            .0
+            .push(MountInfo::builder("/dev/sdb1", "/media/My Disk", "ext4").option("rw").build());
+
+        let mut buffer = Vec::new();
+        mounts.write_to(&mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), mounts.to_mounts_string());
+
+        let string = mounts.to_mounts_string();
+        assert!(string.ends_with("/dev/sdb1 /media/My\\040Disk ext4 rw 0 0\n"));
+        assert_eq!(MountList::parse_from(string.lines()).unwrap(), mounts);
+    }
+
+    #[test]
+    fn missing_dump_pass() {
+        let info = "overlay /merged overlay rw".parse::<MountInfo>().unwrap();
+        assert_eq!(info, MountInfo::builder("overlay", "/merged", "overlay").option("rw").build());
+        assert_eq!((info.dump, info.pass), (0, 0));
+
+        let error = MountInfo::parse_numbered("overlay /merged overlay rw 0 x", 1).unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::InvalidNumber {
+                field:       "pass",
+                line_number: 1,
+                value:       "x".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn refresh_into() {
+        let mut buffer = String::from("stale contents");
+        let mounts = MountList::refresh_into(&mut buffer).unwrap();
+        assert!(!buffer.starts_with("stale"));
+        assert_eq!(mounts, MountList::parse_reader(buffer.as_bytes()).unwrap());
+
+        let capacity = buffer.capacity();
+        MountList::refresh_into(&mut buffer).unwrap();
+        assert!(buffer.capacity() >= capacity);
+    }
+
+    #[test]
+    fn crlf_line_endings() {
+        let info = "/dev/sda1 /boot/efi vfat rw 0 2\r\n".parse::<MountInfo>().unwrap();
+        assert_eq!((info.fstype.as_str(), info.pass), ("vfat", 2));
+
+        let crlf = SAMPLE.replace('\n', "\r\n") + "\r\n";
+        let mounts = MountList::parse_reader(crlf.as_bytes()).unwrap();
+        assert_eq!(mounts, MountList::parse_from(SAMPLE.lines()).unwrap());
+    }
+
+    #[test]
+    fn with_timeout() {
+        use std::{sync::mpsc, time::Duration};
+
+        let result = timeout::with_timeout(Duration::from_secs(5), || Ok(1));
+        assert_eq!(result.unwrap(), 1);
+
+        // Block until the sender is dropped, so that the worker exits with the test.
+        let (tx, rx) = mpsc::channel::<()>();
+        let result = timeout::with_timeout(Duration::from_millis(10), move || {
+            let _ = rx.recv();
+            Ok(())
+        });
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::TimedOut);
+        drop(tx);
+    }
+
+    #[cfg(feature = "libc")]
+    #[test]
+    fn usage_timeout() {
+        use std::time::Duration;
+
+        let root = MountInfo::builder("/dev/sda2", "/", "ext4").build();
+        let usage = root.usage_timeout(Duration::from_secs(30)).unwrap();
+        assert!(usage.total_bytes >= usage.free_bytes);
+        assert!(usage.free_bytes >= usage.available_bytes);
+    }
+
+    #[test]
+    fn blank_lines() {
+        let lines = "/dev/sda2 / ext4 rw 0 0\n   \n\t\n/dev/sda1 /boot/efi vfat rw 0 0\n\n";
+        let mounts = MountList::parse_from(lines.lines()).unwrap();
+        let dests = mounts.iter().map(|mount| mount.dest.as_path()).collect::<Vec<_>>();
+        assert_eq!(dests, vec![Path::new("/"), Path::new("/boot/efi")]);
+
+        let error = MountList::parse_from(" \n/dev/sda1".lines()).unwrap_err();
+        assert_eq!(error.to_string(), "line 2: missing dest");
+
+        assert!(MountList::parse_from(" \n".lines()).unwrap().is_empty());
+
+        let (lenient, errors) = MountList::parse_lenient(lines.lines());
+        assert_eq!(lenient, mounts);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn dump_without_pass() {
+        let info = "/dev/sda6 /mnt/data ext4 rw 1".parse::<MountInfo>().unwrap();
+        assert_eq!((info.dump, info.pass), (1, 0));
+
+        let error = MountInfo::parse_numbered("/dev/sda6 /mnt/data ext4 rw x", 1).unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::InvalidNumber {
+                field:       "dump",
+                line_number: 1,
+                value:       "x".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn escaped_options() {
+        let line = "/dev/sda6 /mnt/data btrfs rw,subvol=/my\\040subvol,x-name=a\\054b 0 0";
+        let info = line.parse::<MountInfo>().unwrap();
+        assert_eq!(info.option_value("subvol"), Some("/my subvol"));
+        assert_eq!(info.option_value("x-name"), Some("a,b"));
+        assert_eq!(info.to_string(), line);
+
+        let error = MountInfo::parse_numbered("/dev/sda6 /mnt btrfs rw,subvol=\\377", 2);
+        assert_eq!(error, Err(ParseError::NonUtf8 { field: "options", line_number: 2 }));
+
+        let info = "/dev/sdb1 /mnt fuse.my\\040fs rw".parse::<MountInfo>().unwrap();
+        assert_eq!(info.fstype, "fuse.my fs");
+    }
+
+    #[test]
+    fn escaped_fstype() {
+        let line = "weird /mnt/weird fuse.weird\\040name rw,nosuid,nodev 0 0";
+        let info = line.parse::<MountInfo>().unwrap();
+        assert_eq!(info.source, PathBuf::from("weird"));
+        assert_eq!(info.dest, PathBuf::from("/mnt/weird"));
+        assert_eq!(info.fstype, "fuse.weird name");
+        assert_eq!(info.options, vec!["rw", "nosuid", "nodev"]);
+        assert_eq!(info.fuse_subtype(), Some("weird name"));
+        assert_eq!(info.to_string(), line);
+
+        let mounts = MountList::parse_from(line.lines()).unwrap();
+        assert_eq!(mounts.with_fstype("fuse.weird name").count(), 1);
+    }
+
+    #[test]
+    fn parse_line_with_raw() {
+        let line = "/dev/sdb1 /media/My\\040Disk ext4 rw,subvol=/a\\040b 0 2";
+        let (info, raw) = MountInfo::parse_line_with_raw(line).unwrap();
+        assert_eq!(info, line.parse::<MountInfo>().unwrap());
+        assert_eq!(
+            raw,
+            RawTokens {
+                source:  "/dev/sdb1".into(),
+                dest:    "/media/My\\040Disk".into(),
+                fstype:  "ext4".into(),
+                options: "rw,subvol=/a\\040b".into(),
+            }
+        );
+
+        assert!(MountInfo::parse_line_with_raw("/dev/sdb1 /media").is_err());
+    }
+
+    #[test]
+    fn parse_value_into() {
+        let mut buffer = b"left over".to_vec();
+        MountInfo::parse_value_into("/mnt/My\\040Disk", 1, &mut buffer).unwrap();
+        assert_eq!(buffer, b"/mnt/My Disk");
+        MountInfo::parse_value_into("/mnt", 1, &mut buffer).unwrap();
+        assert_eq!(buffer, b"/mnt");
+
+        let string = MountInfo::parse_string("a\\054b", "options", 1, &mut buffer).unwrap();
+        assert_eq!(string, "a,b");
+        let string = MountInfo::parse_string("plain", "options", 1, &mut buffer).unwrap();
+        assert_eq!(string, "plain");
+    }
+
+    #[test]
+    fn repeated_spaces() {
+        let info = "sshfs#host:/ /mnt/remote\\040dir fuse.sshfs rw,nosuid  0  0"
+            .parse::<MountInfo>()
+            .unwrap();
+        assert_eq!(info.dest, PathBuf::from("/mnt/remote dir"));
+        assert_eq!(info.options, vec!["rw".to_owned(), "nosuid".to_owned()]);
+        assert_eq!((info.dump, info.pass), (0, 0));
+    }
+
+    #[test]
+    fn iter_from() {
+        let lines = "/dev/sda2 / ext4 rw 0 0\ngarbage\n/dev/sda1 /boot/efi vfat rw 0 0";
+
+        let found = MountList::iter_from(lines.lines())
+            .find(|mount| mount.as_ref().ok().map_or(false, |m| m.dest == Path::new("/")));
+        assert_eq!(found.unwrap().unwrap().source, PathBuf::from("/dev/sda2"));
+
+        let valid = MountList::iter_from(lines.lines()).filter_map(Result::ok).count();
+        assert_eq!(valid, 2);
+        assert!(MountList::parse_from(lines.lines()).is_err());
+    }
+
+    #[test]
+    fn parse_reader() {
+        let mounts = MountList::parse_reader(SAMPLE.as_bytes()).unwrap();
+        assert_eq!(mounts, MountList::parse_from(SAMPLE.lines()).unwrap());
+    }
+
+    #[test]
+    fn mounts_by_dest() {
+        let mounts = MountList::parse_from(
+            "/dev/sda2 / ext4 rw 0 0\n/dev/sdb1 /mnt ext4 rw 0 0\n/dev/sdc1 /mnt xfs rw 0 0"
+                .lines(),
+        )
+        .unwrap();
+
+        let sources = mounts.mounts_by_dest("/mnt").map(|m| m.source.clone()).collect::<Vec<_>>();
+        assert_eq!(sources, vec![PathBuf::from("/dev/sdb1"), PathBuf::from("/dev/sdc1")]);
+        assert_eq!(mounts.get_mount_by_dest("/mnt").unwrap().source, PathBuf::from("/dev/sdb1"));
+        assert_eq!(mounts.mounts_by_dest("/home").count(), 0);
+        assert_eq!(mounts.get_mount_by_dest("/mnt/").unwrap().source, PathBuf::from("/dev/sdb1"));
+        assert!(mounts.get_mount_by_dest("/MNT").is_none());
+    }
+
+    #[test]
+    fn read_only() {
+        let parse = |line: &str| line.parse::<MountInfo>().unwrap();
+        assert!(parse("/dev/sda2 / ext4 ro,noatime 0 0").is_read_only());
+        assert!(!parse("/dev/sda2 / ext4 rw,noatime 0 0").is_read_only());
+        assert!(parse("/dev/sda2 / ext4 rw,noatime,ro 0 0").is_read_only());
+        assert!(!parse("/dev/sda2 / ext4 ro,rw 0 0").is_read_only());
+        assert!(!parse("/dev/sda2 / ext4 defaults 0 0").is_read_only());
+    }
+
+    #[test]
+    fn parsed_options() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+
+        let options = mounts.get_mount_by_dest("/run").unwrap().parsed_options();
+        assert_eq!(
+            options,
+            MountOptions {
+                read_only: false,
+                no_exec:   true,
+                no_suid:   true,
+                no_dev:    false,
+                relatime:  true,
+                noatime:   false,
+                extra:     vec!["size=3291052k".into(), "mode=755".into()],
+            }
+        );
+
+        let options = MountOptions::parse(&["ro", "noatime", "nodev", "rw", "relatime", "dev"]);
+        assert!(!options.read_only);
+        assert!(!options.no_dev);
+        assert!(options.relatime);
+        assert!(!options.noatime);
+        assert!(options.extra.is_empty());
+    }
+
+    #[test]
+    fn parse_errors() {
+        let input = "/dev/sda2 / ext4 rw 0 0\n# comment\n/dev/sda1 /boot/efi vfat rw x 0\n";
+
+        let error = MountList::parse_reader(input.as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            error.get_ref().and_then(|e| e.downcast_ref::<ParseError>()),
+            Some(&ParseError::InvalidNumber {
+                field:       "dump",
+                line_number: 3,
+                value:       "x".into(),
+            })
+        );
+
+        let error = MountList::parse_from("/dev/sda2 / ext4 rw\n/dev/sda1".lines()).unwrap_err();
+        let error = error.get_ref().and_then(|e| e.downcast_ref::<ParseError>()).unwrap();
+        assert_eq!(error, &ParseError::MissingField { field: "dest", line_number: 2 });
+        assert_eq!(error.to_string(), "line 2: missing dest");
+
+        let error = "/dev/sda2 /mnt\\04 ext4 rw".parse::<MountInfo>().unwrap_err();
+        assert_eq!(error.to_string(), "line 1: truncated octal code");
+
+        let error = MountInfo::parse_numbered("/dev/sda2 /mnt\\089 ext4 rw", 7).unwrap_err();
+        assert_eq!(error, ParseError::InvalidOctal { line_number: 7, sequence: "\\08".into() });
+        assert_eq!(error.to_string(), "line 7: invalid octal code: \\08");
+
+        let error = MountInfo::parse_numbered("/dev/sda2 /mnt\\400 ext4 rw", 1).unwrap_err();
+        assert_eq!(error, ParseError::InvalidOctal { line_number: 1, sequence: "\\400".into() });
+    }
+
+    #[test]
+    fn canonicalize() {
+        let dir = env::temp_dir().join(format!("proc-mounts-canonicalize-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("target");
+        let link = dir.join("link");
+        fs::write(&target, b"").unwrap();
+        let _ = fs::remove_file(&link);
+        symlink(&target, &link).unwrap();
+
+        let mut mounts = MountList(vec![
+            MountInfo {
+                source: link.clone(),
+                dest: PathBuf::from("/nonexistent/mount/point"),
+                ..Default::default()
+            },
+            MountInfo { source: PathBuf::from("proc"), dest: link, ..Default::default() },
+        ]);
+        mounts.canonicalize();
+
+        let target = fs::canonicalize(&target).unwrap();
+        assert_eq!(mounts[0].source, target);
+        assert_eq!(mounts[0].dest, PathBuf::from("/nonexistent/mount/point"));
+        assert_eq!(mounts[1].source, PathBuf::from("proc"));
+        assert_eq!(mounts[1].dest, target);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn size_bytes() {
+        let size = |options: &str| {
+            format!("tmpfs /tmp tmpfs {} 0 0", options).parse::<MountInfo>().unwrap().size_bytes()
+        };
+
+        assert_eq!(size("rw,size=16420480k"), Some(16_420_480 * 1024));
+        assert_eq!(size("rw,size=512m"), Some(512 * 1024 * 1024));
+        assert_eq!(size("rw,size=2G"), Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(size("rw,size=4096"), Some(4096));
+        assert_eq!(size("rw,size=50%"), None);
+        assert_eq!(size("rw,nr_inodes=4105120"), None);
+    }
+
+    #[test]
+    fn fstype_filters() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+
+        let dests = |iter: &mut dyn Iterator<Item = &MountInfo>| {
+            iter.map(|m| m.dest.clone()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            dests(&mut mounts.with_fstype("ext4")),
+            vec![PathBuf::from("/"), PathBuf::from("/mnt/data")]
+        );
+        assert_eq!(
+            dests(&mut mounts.real_filesystems()),
+            vec![PathBuf::from("/"), PathBuf::from("/boot/efi"), PathBuf::from("/mnt/data")]
+        );
+    }
+
+    #[test]
+    fn parse_owned() {
+        let mounts = MountList::parse_owned(SAMPLE.to_owned()).unwrap();
+        assert_eq!(mounts, MountList::parse_from(SAMPLE.lines()).unwrap());
+    }
+
+    #[test]
+    fn mounts_by_source() {
+        let lines = [
+            "/dev/sda6 / btrfs rw,subvol=/@ 0 0",
+            "/dev/sda1 /boot/efi vfat rw 0 0",
+            "/dev/sda6 /home btrfs rw,subvol=/@home 0 0",
+        ];
+
+        let mounts = MountList::parse_from(lines.iter().cloned()).unwrap();
+
+        let dests =
+            mounts.mounts_by_source("/dev/sda6").map(|m| m.dest.clone()).collect::<Vec<_>>();
+        assert_eq!(dests, vec![PathBuf::from("/"), PathBuf::from("/home")]);
+        assert_eq!(mounts.get_mount_by_source("/dev/sda6").unwrap().dest, PathBuf::from("/"));
+        assert_eq!(mounts.mounts_by_source("/dev/sdb1").count(), 0);
+    }
+
+    #[test]
+    fn get_mount_by_dest_normalized() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let dest = |path| mounts.get_mount_by_dest_normalized(path).map(|m| m.dest.as_path());
+
+        assert_eq!(dest("/mnt/data/"), Some(Path::new("/mnt/data")));
+        assert_eq!(dest("/boot/efi/"), Some(Path::new("/boot/efi")));
+        assert_eq!(dest("/boot/efi//"), Some(Path::new("/boot/efi")));
+        assert_eq!(dest("/"), Some(Path::new("/")));
+        assert_eq!(dest("//"), Some(Path::new("/")));
+        assert_eq!(dest("/Mnt/Data/"), None);
+        assert_eq!(dest("/mnt/"), None);
+    }
+
+    #[test]
+    fn effective_mount_by_dest() {
+        let mut lines = SAMPLE.lines().collect::<Vec<_>>();
+        lines.push("tmpfs /mnt/data tmpfs rw,size=1024k 0 0");
+        let mounts = MountList::parse_from(lines.into_iter()).unwrap();
+
+        assert_eq!(mounts.get_mount_by_dest("/mnt/data").unwrap().fstype, "ext4");
+        assert_eq!(mounts.effective_mount_by_dest("/mnt/data").unwrap().fstype, "tmpfs");
+        assert_eq!(mounts.effective_mount_by_dest("/boot/efi/").unwrap().fstype, "vfat");
+        assert_eq!(mounts.effective_mount_by_dest("/media"), None);
+    }
+
+    #[test]
+    fn contains() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        assert!(mounts.contains_dest("/boot/efi"));
+        assert!(mounts.contains_dest("/mnt/data/"));
+        assert!(!mounts.contains_dest("/mnt"));
+        assert!(!mounts.contains_dest("/media/usb"));
+
+        assert!(mounts.contains_source("/dev/sda1"));
+        assert!(mounts.contains_source("tmpfs"));
+        assert!(!mounts.contains_source("/dev/sda"));
+        assert!(!mounts.contains_source("/dev/sdb1"));
+    }
+
+    #[test]
+    fn source_mount_count() {
+        let mut lines = SAMPLE.lines().collect::<Vec<_>>();
+        lines.push("/dev/sda6 /srv/data ext4 rw,noatime,data=ordered 0 0");
+        let mounts = MountList::parse_from(lines.into_iter()).unwrap();
+
+        assert_eq!(mounts.source_mount_count("/dev/sda6"), 2);
+        assert_eq!(mounts.source_mount_count("/dev/sda2"), 1);
+        assert_eq!(mounts.source_mount_count("/dev/sdb1"), 0);
+    }
+
+    #[test]
+    fn root_subvolume_mount() {
+        let lines = [
+            "/dev/sda6 / btrfs rw,subvolid=256,subvol=/@ 0 0",
+            "/dev/sda6 /mnt/pool btrfs rw,subvolid=5,subvol=/ 0 0",
+            "/dev/sdb1 /mnt/backup btrfs rw,subvol=/ 0 0",
+            "/dev/sda1 /boot/efi vfat rw 0 0",
+        ];
+
+        let mounts = MountList::parse_from(lines.iter().cloned()).unwrap();
+        let mount = |source| mounts.root_subvolume_mount(source).map(|m| m.dest.clone());
+        assert_eq!(mount("/dev/sda6"), Some(PathBuf::from("/mnt/pool")));
+        assert_eq!(mount("/dev/sdb1"), Some(PathBuf::from("/mnt/backup")));
+        assert_eq!(mount("/dev/sda1"), None);
+    }
+
+    #[test]
+    fn is_removable() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let error = mounts.get_mount_by_dest("/run").unwrap().is_removable().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn is_encrypted() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        assert!(!mounts.get_mount_by_dest("/run").unwrap().is_encrypted().unwrap());
+
+        let null = MountInfo::builder("/dev/null", "/mnt", "ext4").build();
+        assert!(!null.is_encrypted().unwrap());
+
+        let missing = MountInfo::builder("/dev/mapper/does-not-exist", "/mnt", "ext4").build();
+        assert_eq!(missing.is_encrypted().unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn overlay_paths() {
+        let info = "overlay /var/lib/docker/overlay2/abc/merged overlay \
+                    rw,relatime,lowerdir=/var/lib/docker/overlay2/l/AAA:/var/lib/docker/overlay2/\
+                    l/BBB:/layers/with\\134:colon,upperdir=/var/lib/docker/overlay2/abc/diff,\
+                    workdir=/var/lib/docker/overlay2/abc/work 0 0"
+            .parse::<MountInfo>()
+            .unwrap();
+
+        assert_eq!(
+            info.overlay_paths().unwrap(),
+            OverlayPaths {
+                lowerdir: vec![
+                    PathBuf::from("/var/lib/docker/overlay2/l/AAA"),
+                    PathBuf::from("/var/lib/docker/overlay2/l/BBB"),
+                    PathBuf::from("/layers/with:colon"),
+                ],
+                upperdir: Some(PathBuf::from("/var/lib/docker/overlay2/abc/diff")),
+                workdir:  Some(PathBuf::from("/var/lib/docker/overlay2/abc/work")),
+            }
+        );
+
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        assert_eq!(mounts.get_mount_by_dest("/").unwrap().overlay_paths(), None);
+    }
+
+    #[test]
+    fn reconcile() {
+        let live = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let fstab = [
+            "UUID=0a1b2c3d / ext4 errors=remount-ro 0 1",
+            "/dev/sdb1 /media/backup ext4 defaults 0 2",
+            "/swapfile none swap sw 0 0",
+        ];
+        let fstab = MountList::parse_fstab(fstab.iter().cloned()).unwrap();
+
+        let reconciliation = live.reconcile(&fstab);
+        assert_eq!(reconciliation.mounted, vec![&fstab[0]]);
+        assert_eq!(reconciliation.missing, vec![&fstab[1]]);
+
+        let extra = reconciliation.extra.iter().map(|mount| mount.dest.as_path());
+        assert_eq!(
+            extra.collect::<Vec<_>>(),
+            vec![
+                Path::new("/sys"),
+                Path::new("/proc"),
+                Path::new("/dev"),
+                Path::new("/run"),
+                Path::new("/sys/fs/fuse/connections"),
+                Path::new("/boot/efi"),
+                Path::new("/mnt/data"),
+            ]
+        );
+    }
+
+    #[test]
+    fn eq_ignoring_options() {
+        let before = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let resized = SAMPLE.replace("size=3291052k", "size=4096000k");
+        let after = MountList::parse_from(resized.lines()).unwrap();
+        let is_size = |option: &str| option.starts_with("size=");
+
+        assert_ne!(before, after);
+        assert!(before.eq_ignoring_options(&after, is_size));
+        assert!(!before.eq_ignoring_options(&after, |_| false));
+
+        let remounted = SAMPLE.replace("tmpfs /run tmpfs rw,", "tmpfs /run tmpfs ro,");
+        let remounted = MountList::parse_from(remounted.lines()).unwrap();
+        assert!(!before.eq_ignoring_options(&remounted, is_size));
+
+        let fewer = MountList::parse_from(SAMPLE.lines().skip(1)).unwrap();
+        assert!(!before.eq_ignoring_options(&fewer, |_| true));
+    }
+
+    #[test]
+    fn diff() {
+        let old = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let mut new = old.clone();
+        new.0.retain(|mount| mount.dest != Path::new("/sys/fs/fuse/connections"));
+        new.0.push("/dev/sdb1 /media/usb vfat rw 0 0".parse().unwrap());
+        new.0.iter_mut().find(|m| m.dest == Path::new("/mnt/data")).unwrap().options[0] =
+            "ro".into();
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.added, vec![new.get_mount_by_dest("/media/usb").unwrap()]);
+        assert_eq!(diff.removed, vec![old.get_mount_by_dest("/sys/fs/fuse/connections").unwrap()]);
+        assert_eq!(
+            diff.changed,
+            vec![(
+                old.get_mount_by_dest("/mnt/data").unwrap(),
+                new.get_mount_by_dest("/mnt/data").unwrap()
+            )]
+        );
+        assert!(diff.changed[0].1.is_read_only());
+        assert!(old.diff(&old).is_empty());
+
+        let mut lines = SAMPLE.lines().collect::<Vec<_>>();
+        lines.push("tmpfs /run tmpfs rw,size=1024k 0 0");
+        let overmounted = MountList::parse_from(lines.iter().cloned()).unwrap();
+        assert!(overmounted.diff(&overmounted).is_empty());
+
+        let diff = old.diff(&overmounted);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.changed, vec![(&old[3], &overmounted[8])]);
+
+        let diff = overmounted.diff(&old);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.changed, vec![(&overmounted[8], &old[3])]);
+    }
+
+    #[test]
+    fn builder() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+
+        let info = MountInfo::builder("/dev/sda1", "/boot/efi", "vfat")
+            .option("rw")
+            .options(vec!["relatime", "fmask=0077", "dmask=0077", "codepage=437"])
+            .options(vec!["iocharset=iso8859-1".to_owned(), "shortname=mixed".to_owned()])
+            .option("errors=remount-ro")
+            .build();
+        assert_eq!(&info, mounts.get_mount_by_dest("/boot/efi").unwrap());
+
+        let info = MountInfo::builder("/dev/sda2", "/", "ext4").dump(1).pass(1).build();
+        assert!(info.options.is_empty());
+        assert_eq!((info.dump, info.pass), (1, 1));
+    }
+
+    #[test]
+    fn parse_lenient() {
+        let input = SAMPLE.replace("fusectl rw,relatime 0 0", "fusectl rw,relatime x 0");
+
+        let (mounts, errors) = MountList::parse_lenient(input.lines());
+        assert_eq!(mounts.len(), 7);
+        assert_eq!(
+            errors,
+            vec![(
+                6,
+                ParseError::InvalidNumber {
+                    field:       "dump",
+                    line_number: 6,
+                    value:       "x".into(),
+                }
+            )]
+        );
+
+        let (mounts, errors) = MountList::parse_lenient(SAMPLE.lines());
+        assert_eq!(mounts, MountList::parse_from(SAMPLE.lines()).unwrap());
+        assert!(errors.is_empty());
+    }
+
+    #[cfg(feature = "libc")]
+    #[test]
+    fn usage() {
+        let root = MountInfo { dest: PathBuf::from("/"), ..Default::default() };
+        let usage = root.usage().unwrap();
+        assert!(usage.total_bytes >= usage.free_bytes);
+        assert!(usage.free_bytes >= usage.available_bytes);
+
+        let missing = MountInfo { dest: PathBuf::from("/nonexistent/mount"), ..Default::default() };
+        assert_eq!(missing.usage().unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn from_pid() {
+        let mounts = MountList::from_pid(process::id()).unwrap();
+        assert!(mounts.get_mount_by_dest("/").is_some());
+        assert!(MountInfoList::from_pid_mountinfo(process::id()).is_ok());
+
+        let error = MountList::from_pid(u32::max_value()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        let error = MountInfoList::from_pid_mountinfo(u32::max_value()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn eq_ignoring_option_order() {
+        let a = "/dev/sda2 / ext4 rw,noatime 0 1".parse::<MountInfo>().unwrap();
+        let b = "/dev/sda2 / ext4 noatime,rw 0 1".parse::<MountInfo>().unwrap();
+        assert_ne!(a, b);
+        assert!(a.eq_ignoring_option_order(&b));
+
+        let c = "/dev/sda2 / ext4 noatime,ro 0 1".parse::<MountInfo>().unwrap();
+        assert!(!a.eq_ignoring_option_order(&c));
+        let d = "/dev/sda2 / ext4 noatime,rw 0 2".parse::<MountInfo>().unwrap();
+        assert!(!a.eq_ignoring_option_order(&d));
+    }
+
+    #[cfg(feature = "libc")]
+    #[test]
+    fn device_id() {
+        let proc = MountInfo { dest: PathBuf::from("/proc"), ..Default::default() };
+        assert_eq!(proc.device_id().unwrap().0, 0);
+
+        let missing = MountInfo { dest: PathBuf::from("/nonexistent/mount"), ..Default::default() };
+        assert_eq!(missing.device_id().unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn iter_with_children() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let pairs = mounts
+            .iter_with_children()
+            .map(|(mount, children)| {
+                (mount.dest.as_path(), children.iter().map(|child| child.dest.as_path()).collect())
+            })
+            .collect::<Vec<(&Path, Vec<&Path>)>>();
+
+        assert_eq!(pairs.len(), mounts.len());
+        assert_eq!(pairs[0].0, Path::new("/"));
+        assert_eq!(
+            pairs[0].1,
+            vec![
+                Path::new("/sys"),
+                Path::new("/proc"),
+                Path::new("/dev"),
+                Path::new("/run"),
+                Path::new("/boot/efi"),
+                Path::new("/mnt/data"),
+            ]
+        );
+
+        assert_eq!(pairs[1], (Path::new("/sys"), vec![Path::new("/sys/fs/fuse/connections")]));
+        assert_eq!(pairs[2], (Path::new("/sys/fs/fuse/connections"), Vec::new()));
+    }
+
+    #[test]
+    fn tree() {
+        let mut lines = SAMPLE.lines().collect::<Vec<_>>();
+        lines.push("/dev/sdb1 /boot/efi/extra vfat rw 0 0");
+        lines.push("tmpfs /run tmpfs rw 0 0");
+        let mounts = MountList::parse_from(lines.into_iter()).unwrap();
+        let tree = mounts.tree();
+
+        fn dest<'a>(node: &MountNode<'a>) -> &'a Path { &node.mount.unwrap().dest }
+
+        fn children<'a>(node: &MountNode<'a>) -> Vec<&'a Path> {
+            node.children.iter().map(dest).collect()
+        }
+
+        assert_eq!(dest(&tree), Path::new("/"));
+        assert_eq!(
+            children(&tree),
+            vec![
+                Path::new("/sys"),
+                Path::new("/proc"),
+                Path::new("/dev"),
+                Path::new("/run"),
+                Path::new("/boot/efi"),
+                Path::new("/mnt/data"),
+            ]
+        );
+
+        assert_eq!(children(&tree.children[0]), vec![Path::new("/sys/fs/fuse/connections")]);
+        assert_eq!(children(&tree.children[3]), vec![Path::new("/run")]);
+        assert_eq!(children(&tree.children[4]), vec![Path::new("/boot/efi/extra")]);
+    }
+
+    #[test]
+    fn fstypes() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let fstypes = ["devtmpfs", "ext4", "fusectl", "proc", "sysfs", "tmpfs", "vfat"];
+        assert_eq!(mounts.fstypes().iter().collect::<Vec<_>>(), fstypes);
+        assert!(MountList::default().fstypes().is_empty());
+    }
+
+    #[test]
+    fn filter_fstypes() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+
+        let dests = mounts.filter_fstypes(|fstype| fstype == "ext4").map(|m| &m.dest);
+        assert_eq!(dests.collect::<Vec<_>>(), vec![Path::new("/"), Path::new("/mnt/data")]);
+
+        let real = mounts
+            .filter_fstypes(|fstype| fstype == "tmpfs" || !PSEUDO_FILESYSTEMS.contains(&fstype));
+        assert_eq!(real.count(), mounts.real_filesystems().count() + 1);
+    }
+
+    #[test]
+    fn is_mount_point() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        assert!(mounts.is_mount_point("/boot/efi"));
+        assert!(mounts.is_mount_point("/boot/efi/"));
+        assert!(!mounts.is_mount_point("/boot"));
+    }
+
+    #[test]
+    fn fuse_subtype() {
+        let info =
+            "sshfs#host:/ /mnt/remote fuse.sshfs rw,nosuid 0 0".parse::<MountInfo>().unwrap();
+        assert!(info.is_fuse());
+        assert_eq!(info.fuse_subtype(), Some("sshfs"));
+
+        let info = "/dev/sdb1 /mnt/win fuseblk rw 0 0".parse::<MountInfo>().unwrap();
+        assert!(info.is_fuse());
+        assert_eq!(info.fuse_subtype(), None);
+
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let fusectl = mounts.get_mount_by_dest("/sys/fs/fuse/connections").unwrap();
+        assert!(!fusectl.is_fuse());
+        assert_eq!(fusectl.fuse_subtype(), None);
+    }
+
+    #[test]
+    fn iter_refs() {
+        let contents = [SAMPLE, "", "/dev/sdb1 /media/My\\040Disk ext4 rw 0 2"].join("\n");
+
+        let refs = MountList::iter_refs(&contents).collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(refs.len(), 9);
+
+        let root = &refs[4];
+        assert_eq!(root.source, Cow::Borrowed(OsStr::new("/dev/sda2")));
+        assert_eq!(root.fstype, "ext4");
+        assert_eq!(root.options, "rw,noatime,errors=remount-ro,data=ordered");
+
+        let disk = &refs[8];
+        assert_eq!(disk.dest, Cow::<OsStr>::Owned(OsString::from("/media/My Disk")));
+        assert_eq!(disk.pass, 2);
+
+        let owned = refs.iter().map(MountInfoRef::to_owned).collect::<io::Result<Vec<_>>>();
+        let mounts = MountList::new_from_reader(contents.as_bytes()).unwrap();
+        assert_eq!(MountList(owned.unwrap()), mounts);
+
+        let error = MountList::iter_refs("\nproc /proc").next().unwrap().unwrap_err();
+        let error = error.get_ref().and_then(|e| e.downcast_ref::<ParseError>()).unwrap();
+        assert_eq!(error, &ParseError::MissingField { field: "type", line_number: 2 });
+
+        // Comments are not skipped, just as by `parse_from`.
+        let comment = "# comment";
+        let error = MountList::iter_refs(comment).next().unwrap().unwrap_err();
+        let expected = MountList::parse_from(comment.lines()).unwrap_err();
+        assert_eq!(error.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn iter_refs_eq_ignores_line_number() {
+        let entry = "/dev/sda1 /boot/efi vfat rw 0 0";
+        let contents = [entry, "", entry].join("\n");
+
+        let refs = MountList::iter_refs(&contents).collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(refs[0], refs[1]);
+        assert_eq!(refs.iter().collect::<HashSet<_>>().len(), 1);
+    }
+
+    #[test]
+    fn cache() {
+        let path = env::temp_dir().join(format!("proc-mounts-cache-{}", process::id()));
+        fs::write(&path, SAMPLE).unwrap();
+
+        let mut cache = MountCache::new_from_file(&path).unwrap();
+        assert_eq!(cache.mounts(), &MountList::parse_from(SAMPLE.lines()).unwrap());
+        assert!(!cache.refresh().unwrap());
+
+        let lines = [SAMPLE, "/dev/sdb1 /media/usb vfat rw 0 0"].join("\n");
+        assert!(cache.refresh_from_reader(lines.as_bytes()).unwrap());
+        assert!(cache.is_mount_point("/media/usb"));
+        assert!(!cache.refresh_from_reader(lines.as_bytes()).unwrap());
+
+        assert!(cache.refresh().unwrap());
+        assert!(!cache.is_mount_point("/media/usb"));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn mounts_under() {
+        let lines = [
+            "/dev/sda6 /mnt/data ext4 rw 0 0",
+            "/dev/sdb1 /mnt/data/a ext4 rw 0 0",
+            "/dev/sdb2 /mnt/data/a/b/c ext4 rw 0 0",
+            "/dev/sdb3 /mnt/data/b ext4 rw 0 0",
+            "/dev/sdb4 /mnt/database ext4 rw 0 0",
+            "/dev/sdb5 /mnt/data/a/b ext4 rw 0 0",
+        ];
+
+        let mounts = MountList::parse_from(lines.iter().cloned()).unwrap();
+        let dests = |path| {
+            mounts.mounts_under(Path::new(path)).into_iter().map(|m| &m.dest).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            dests("/mnt/data"),
+            vec![
+                Path::new("/mnt/data/a/b/c"),
+                Path::new("/mnt/data/a/b"),
+                Path::new("/mnt/data/b"),
+                Path::new("/mnt/data/a"),
+            ]
+        );
+        assert!(dests("/mnt/dat").is_empty());
+        assert_eq!(dests("/mnt").len(), 6);
+    }
+
+    #[test]
+    fn starts_with() {
+        let lines = [
+            "/dev/sda1 /boot vfat rw 0 0",
+            "/dev/sda2 /bootmgr ext4 rw 0 0",
+            "/dev/sda3 /boot/efi vfat rw 0 0",
+            "/dev/sdb1 /mnt ext4 rw 0 0",
+        ];
+
+        let mounts = MountList::parse_from(lines.iter().cloned()).unwrap();
+        let path = Path::new("/boot");
+        let dests = mounts.destination_starts_with(path).map(|m| &m.dest).collect::<Vec<_>>();
+        assert_eq!(dests, vec![Path::new("/boot"), Path::new("/boot/efi")]);
+        assert_eq!(mounts.destination_starts_with_bytes(path).count(), 3);
+
+        let path = Path::new("/dev/sda");
+        assert_eq!(mounts.source_starts_with(path).count(), 0);
+        assert_eq!(mounts.source_starts_with_bytes(path).count(), 3);
+        assert_eq!(mounts.source_starts_with(Path::new("/dev")).count(), 4);
+    }
+
+    #[test]
+    fn parse_fstab() {
+        let lines = [
+            "# /etc/fstab: static file system information.",
+            "",
+            "UUID=0a1b2c3d /\text4\terrors=remount-ro 0       1",
+            "   # /boot/efi was on /dev/sda1 during installation",
+            "/dev/sda1  /boot/efi  vfat  umask=0077  0  1",
+        ];
+
+        let mounts = MountList::parse_fstab(lines.iter().cloned()).unwrap();
+        assert_eq!(mounts.len(), 2);
+        assert_eq!(mounts[0].source, PathBuf::from("UUID=0a1b2c3d"));
+        assert_eq!(mounts[0].options, vec!["errors=remount-ro".to_owned()]);
+        assert_eq!(mounts[0].pass, 1);
+        assert_eq!(mounts[1].dest, PathBuf::from("/boot/efi"));
+
+        let error = MountList::parse_fstab(["# comment", "", "proc /proc"].iter().cloned());
+        let error = error.unwrap_err();
+        let error = error.get_ref().and_then(|e| e.downcast_ref::<ParseError>()).unwrap();
+        assert_eq!(error, &ParseError::MissingField { field: "type", line_number: 3 });
+    }
+
+    #[test]
+    fn has_fstype() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        assert!(mounts.has_fstype("ext4"));
+        assert!(!mounts.has_fstype("xfs"));
+        assert_eq!(mounts.first_with_fstype("ext4").unwrap().dest, PathBuf::from("/"));
+        assert_eq!(mounts.first_with_fstype("xfs"), None);
+    }
+
+    #[test]
+    fn device_names() {
+        let names = |source| {
+            let info = MountInfo { source: PathBuf::from(source), ..Default::default() };
+            let name = |name: Option<&OsStr>| name.map(|name| name.to_str().unwrap().to_owned());
+            (name(info.source_device_name()), name(info.parent_disk_name()))
+        };
+
+        let some = |device: &str, disk: &str| (Some(device.to_owned()), Some(disk.to_owned()));
+        assert_eq!(names("/dev/sda1"), some("sda1", "sda"));
+        assert_eq!(names("/dev/sda"), some("sda", "sda"));
+        assert_eq!(names("/dev/vdb12"), some("vdb12", "vdb"));
+        assert_eq!(names("/dev/nvme0n1p2"), some("nvme0n1p2", "nvme0n1"));
+        assert_eq!(names("/dev/nvme0n1"), some("nvme0n1", "nvme0n1"));
+        assert_eq!(names("/dev/mmcblk0p1"), some("mmcblk0p1", "mmcblk0"));
+        assert_eq!(names("/dev/loop3"), some("loop3", "loop3"));
+        assert_eq!(names("/dev/mapper/root"), some("root", "root"));
+        assert_eq!(names("/dev/dm-0"), some("dm-0", "dm-0"));
+        assert_eq!(names("tmpfs"), (None, None));
+    }
+
+    #[test]
+    fn unmount_order() {
+        let mut lines = SAMPLE.lines().collect::<Vec<_>>();
+        lines.push("tmpfs /run tmpfs rw 0 0");
+        let mounts = MountList::parse_from(lines.into_iter()).unwrap();
+
+        let order = mounts.unmount_order();
+        let dests = order.iter().map(|m| m.dest.as_path()).collect::<Vec<_>>();
+        assert_eq!(
+            dests,
+            vec![
+                Path::new("/sys/fs/fuse/connections"),
+                Path::new("/mnt/data"),
+                Path::new("/boot/efi"),
+                Path::new("/run"),
+                Path::new("/run"),
+                Path::new("/dev"),
+                Path::new("/proc"),
+                Path::new("/sys"),
+                Path::new("/"),
+            ]
+        );
+        assert_eq!(order[3].options, vec!["rw".to_owned()]);
+    }
+
+    #[test]
+    fn from_fd() {
+        let path = env::temp_dir().join(format!("proc-mounts-from-fd-{}", process::id()));
+        fs::write(&path, SAMPLE).unwrap();
+
+        let mut file = fs::File::open(&path).unwrap();
+        let mounts = unsafe { MountList::from_fd(file.as_raw_fd()) }.unwrap();
+        assert_eq!(mounts, MountList::parse_from(SAMPLE.lines()).unwrap());
+
+        // The descriptor remains open, and owned by the file.
+        file.seek(SeekFrom::Start(0)).unwrap();
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, SAMPLE);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn len() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        assert_eq!(mounts.len(), 8);
+        assert!(!mounts.is_empty());
+        assert!(MountList::default().is_empty());
+    }
+
+    #[test]
+    fn dedup() {
+        let lines = [
+            "/dev/sda6 /srv ext4 rw 0 0",
+            "/dev/sda6 /srv ext4 rw 0 0",
+            "/dev/sda6 /srv ext4 ro 0 0",
+            "/dev/sda1 /boot/efi vfat rw 0 0",
+            "/dev/sda6 /srv ext4 rw 0 0",
+        ];
+
+        let mounts = MountList::parse_from(lines.iter().cloned()).unwrap();
+        let deduped = mounts.dedup();
+        assert_eq!(
+            deduped,
+            MountList(vec![mounts[0].clone(), mounts[2].clone(), mounts[3].clone()])
+        );
+        assert_eq!(mounts.len(), 5);
+    }
+
+    #[test]
+    fn sort_by_dest() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let sorted = mounts.sorted_by_dest();
+        let dests = sorted.iter().map(|m| m.dest.as_path()).collect::<Vec<_>>();
+        assert_eq!(
+            dests,
+            vec![
+                Path::new("/"),
+                Path::new("/boot/efi"),
+                Path::new("/dev"),
+                Path::new("/mnt/data"),
+                Path::new("/proc"),
+                Path::new("/run"),
+                Path::new("/sys"),
+                Path::new("/sys/fs/fuse/connections"),
+            ]
+        );
+
+        let mut mounts = mounts;
+        mounts.sort_by_dest();
+        assert_eq!(mounts, sorted);
+    }
+
+    #[test]
+    fn cgroup_controllers() {
+        let lines = [
+            "cgroup /sys/fs/cgroup/cpu,cpuacct cgroup rw,nosuid,nodev,noexec,relatime,cpu,cpuacct \
+             0 0",
+            "cgroup /sys/fs/cgroup/systemd cgroup \
+             rw,nosuid,nodev,noexec,relatime,xattr,name=systemd 0 0",
+            "cgroup2 /sys/fs/cgroup/unified cgroup2 rw,nosuid,nodev,noexec,relatime 0 0",
+        ];
+
+        let mounts = MountList::parse_from(lines.iter().cloned()).unwrap();
+        assert_eq!(mounts[0].cgroup_controllers(), Some(vec!["cpu", "cpuacct"]));
+        assert_eq!(mounts[1].cgroup_controllers(), Some(Vec::new()));
+        assert_eq!(mounts[2].cgroup_controllers(), None);
+    }
+
+    #[test]
+    fn has_block_source() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let block = mounts.iter().filter(|mount| mount.has_block_source()).map(|m| &m.dest);
+        assert_eq!(
+            block.collect::<Vec<_>>(),
+            vec![Path::new("/"), Path::new("/boot/efi"), Path::new("/mnt/data")]
+        );
+    }
+
+    #[cfg(feature = "libc")]
+    #[test]
+    fn source_is_block_device() {
+        let proc_mount = MountInfo::builder("proc", "/proc", "proc").build();
+        assert!(!proc_mount.source_is_block_device().unwrap());
+
+        let null = MountInfo::builder("/dev/null", "/mnt", "ext4").build();
+        assert!(null.has_block_source());
+        assert!(!null.source_is_block_device().unwrap());
+
+        let missing = MountInfo::builder("/dev/does-not-exist", "/mnt", "ext4").build();
+        let error = missing.source_is_block_device().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[cfg(feature = "libc")]
+    #[test]
+    fn same_device_as() {
+        let dir = env::temp_dir().join(format!("proc-mounts-same-device-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let link = dir.join("by-label");
+        let _ = fs::remove_file(&link);
+        symlink("/dev/null", &link).unwrap();
+
+        let live = MountInfo::builder("/dev/null", "/mnt", "ext4").build();
+        let linked = MountInfo::builder(&link, "/mnt", "ext4").build();
+        let other = MountInfo::builder("/dev/zero", "/mnt", "ext4").build();
+        let result = (live.same_device_as(&linked), live.same_device_as(&other));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(result.0.unwrap());
+        assert!(!result.1.unwrap());
+
+        let tmpfs = MountInfo::builder("tmpfs", "/run", "tmpfs").build();
+        assert!(tmpfs.same_device_as(&tmpfs.clone()).unwrap());
+        assert!(!tmpfs.same_device_as(&live).unwrap());
+
+        let tagged = MountInfo::builder("UUID=does-not-exist", "/", "ext4").build();
+        let error = tagged.same_device_as(&live).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[cfg(feature = "libc")]
+    #[test]
+    fn mount_by_device() {
+        use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+        let lines = ["tmpfs /run tmpfs rw 0 0", "/dev/null /mnt/null none rw 0 0"];
+        let mounts = MountList::parse_from(lines.iter().cloned()).unwrap();
+        let null = fs::metadata("/dev/null").unwrap().rdev();
+        let (major, minor) = (libc::major(null), libc::minor(null));
+        assert_eq!(mounts.mount_by_device(major, minor).unwrap(), None);
+
+        let device = fs::read_dir("/dev").unwrap().filter_map(Result::ok).find(|entry| {
+            entry.file_type().map(|file_type| file_type.is_block_device()).unwrap_or(false)
+        });
+
+        if let Some(device) = device {
+            let line = format!("{} /mnt/block ext4 rw 0 0", device.path().display());
+            let mounts = MountList::parse_from(lines.iter().cloned().chain(Some(line.as_str())));
+            let mounts = mounts.unwrap();
+            let rdev = device.metadata().unwrap().rdev();
+            let mount = mounts.mount_by_device(libc::major(rdev), libc::minor(rdev)).unwrap();
+            assert_eq!(mount.unwrap().dest, PathBuf::from("/mnt/block"));
+        }
+    }
+
+    #[test]
+    fn root_is_read_only() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        assert_eq!(mounts.root_is_read_only(), Some(false));
+
+        let lines = ["/dev/sda2 / ext4 ro,relatime 0 0", "proc /proc proc rw 0 0"];
+        let mounts = MountList::parse_from(lines.iter().cloned()).unwrap();
+        assert_eq!(mounts.root_is_read_only(), Some(true));
+
+        let lines = ["/dev/sda2 / ext4 rw,ro 0 0", "/dev/sda2 / ext4 ro,rw 0 0"];
+        let mounts = MountList::parse_from(lines.iter().cloned()).unwrap();
+        assert_eq!(mounts.root_is_read_only(), Some(false));
+
+        let mounts = MountList::parse_from(["proc /proc proc rw 0 0"].iter().cloned()).unwrap();
+        assert_eq!(mounts.root_is_read_only(), None);
+    }
+
+    #[test]
+    fn is_submount_of() {
+        let mount = |dest| MountInfo::builder("/dev/sda6", dest, "ext4").build();
+        let data = mount("/mnt/data");
+
+        assert!(data.is_submount_of(&mount("/mnt")));
+        assert!(data.is_submount_of(&mount("/")));
+        assert!(!data.is_submount_of(&mount("/m")));
+        assert!(!data.is_submount_of(&mount("/mnt/data")));
+        assert!(!data.is_submount_of(&mount("/mnt/data/inner")));
+        assert!(!mount("/mnt").is_submount_of(&data));
+    }
+
+    #[test]
+    fn octal_round_trip() {
+        let path = OsStr::new("/media/My Disk\\backup");
+        let encoded = encode_octal(path);
+        assert_eq!(encoded, "/media/My\\040Disk\\134backup");
+        assert_eq!(decode_octal(&encoded).unwrap(), path);
+
+        let path = OsStr::from_bytes(b"/media/\xff\tdisk");
+        let encoded = encode_octal(path);
+        assert_eq!(encoded, "/media/\\377\\011disk");
+        assert_eq!(decode_octal(&encoded).unwrap(), path);
+
+        assert_eq!(decode_octal("/media/\\04").unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        let error = decode_octal("/media/\\400").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "invalid octal code: \\400");
+        assert_eq!(decode_octal("\\04").unwrap_err().to_string(), "truncated octal code");
+    }
+
+    #[test]
+    fn loop_backing_file() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        assert!(mounts.iter().all(|mount| mount.loop_backing_file().unwrap().is_none()));
+
+        let image = "/dev/loop9999 /mnt/image ext4 ro 0 0".parse::<MountInfo>().unwrap();
+        let error = image.loop_backing_file().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+
+        let control = "/dev/loop-control /mnt/x ext4 ro 0 0".parse::<MountInfo>().unwrap();
+        assert!(control.loop_backing_file().unwrap().is_none());
+    }
+
+    #[test]
+    fn to_fstab_line_with_uuid() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let run = mounts.get_mount_by_dest("/run").unwrap();
+        assert_eq!(run.to_fstab_line_with_uuid().unwrap(), run.to_string());
+
+        let null = MountInfo::builder("/dev/null", "/mnt/null", "ext4").option("rw").build();
+        assert_eq!(null.to_fstab_line_with_uuid().unwrap(), "/dev/null /mnt/null ext4 rw 0 0");
+
+        let missing = MountInfo::builder("/dev/does-not-exist", "/mnt", "ext4").build();
+        let error = missing.to_fstab_line_with_uuid().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn systemd_options() {
+        let options = [
+            "rw",
+            "noauto",
+            "x-systemd.automount",
+            "x-systemd.idle-timeout=1min",
+            "comment=systemd.device-timeout=10s",
+            "comment=other",
+        ];
+        let info = MountInfo::builder("/dev/sdb1", "/mnt/backup", "ext4");
+        let info = info.options(options.iter().cloned()).build();
+        let expected = vec![
+            ("automount", None),
+            ("idle-timeout", Some("1min")),
+            ("device-timeout", Some("10s")),
+        ];
+        assert_eq!(info.systemd_options(), expected);
+
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        assert!(mounts.iter().all(|mount| mount.systemd_options().is_empty()));
+    }
+
+    #[test]
+    fn hardened_options() {
+        let shm = "tmpfs /dev/shm tmpfs rw,nosuid,nodev,noexec,relatime 0 0";
+        let shm = shm.parse::<MountInfo>().unwrap();
+        assert!(shm.is_noexec() && shm.is_nosuid() && shm.is_nodev());
+        assert!(shm.is_hardened());
+
+        let shm = "tmpfs /dev/shm tmpfs rw,nosuid,nodev,noexec,exec 0 0";
+        let shm = shm.parse::<MountInfo>().unwrap();
+        assert!(!shm.is_noexec());
+        assert!(!shm.is_hardened());
+
+        let shm = "tmpfs /dev/shm tmpfs rw,suid,dev,nosuid,nodev,noexec 0 0";
+        assert!(shm.parse::<MountInfo>().unwrap().is_hardened());
+
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let run = mounts.get_mount_by_dest("/run").unwrap();
+        assert!(run.is_noexec() && run.is_nosuid() && !run.is_nodev());
+        assert!(!run.is_hardened());
+    }
+
+    #[test]
+    fn network_source() {
+        let nfs = "nas:/srv/export /mnt/nfs nfs4 rw,vers=4.2 0 0".parse::<MountInfo>().unwrap();
+        assert_eq!(
+            nfs.network_source(),
+            Some(NetworkSource::Nfs { host: "nas".into(), export: "/srv/export".into() })
+        );
+
+        let nfs = "[fe80::1]:/srv/export /mnt/nfs nfs rw 0 0".parse::<MountInfo>().unwrap();
+        assert_eq!(
+            nfs.network_source(),
+            Some(NetworkSource::Nfs { host: "fe80::1".into(), export: "/srv/export".into() })
+        );
+
+        let cifs = "//nas/media/films /mnt/films cifs rw 0 0".parse::<MountInfo>().unwrap();
+        assert_eq!(
+            cifs.network_source(),
+            Some(NetworkSource::Cifs { host: "nas".into(), share: "media/films".into() })
+        );
+
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        assert!(mounts.iter().all(|mount| mount.network_source().is_none()));
+    }
+
+    #[test]
+    fn options_string() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let vfat = mounts.get_mount_by_dest("/boot/efi").unwrap();
+        let options = "rw,relatime,fmask=0077,dmask=0077,codepage=437,iocharset=iso8859-1,\
+                       shortname=mixed,errors=remount-ro";
+        assert_eq!(vfat.options_string(), options);
+        assert_eq!(vfat.options_string().split(',').collect::<Vec<_>>(), vfat.options);
+    }
+
+    #[test]
+    fn collect_mount_list() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let real = mounts.real_filesystems().cloned().collect::<MountList>();
+        assert_eq!(
+            real,
+            MountList::from_entries(vec![mounts[4].clone(), mounts[6].clone(), mounts[7].clone()])
+        );
+    }
+
+    #[test]
+    fn option_value() {
+        let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
+        let udev = mounts.get_mount_by_dest("/dev").unwrap();
+        assert_eq!(udev.option_value("size"), Some("16420480k"));
+        assert_eq!(udev.option_value("nr_inodes"), Some("4105120"));
+        assert_eq!(udev.option_value("inodes"), None);
+        assert_eq!(udev.option_value("rw"), None);
+        assert!(udev.has_flag("rw"));
+        assert!(udev.has_flag("nosuid"));
+        assert!(!udev.has_flag("size"));
+        assert!(!udev.has_flag("ro"));
+    }
+
     #[test]
     fn mounts() {
         let mounts = MountList::parse_from(SAMPLE.lines()).unwrap();
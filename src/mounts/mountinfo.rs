@@ -0,0 +1,270 @@
+use super::MountInfo;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use ParseError;
+
+/// A mount entry parsed from `/proc/self/mountinfo`.
+///
+/// In addition to the fields found in `/proc/mounts`, this records the mount and parent IDs,
+/// the device number, the root of the mount within its file system, and the propagation state.
+#[derive(Debug, Default, Clone, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MountInfoEntry {
+    /// A unique ID for the mount.
+    pub mount_id: u32,
+    /// The ID of the parent mount, or of itself for the root of the mount tree.
+    pub parent_id: u32,
+    /// The major and minor device numbers of the file system.
+    pub dev: (u32, u32),
+    /// The path within the file system which forms the root of this mount.
+    pub root: PathBuf,
+    /// Where the source is mounted, relative to the process's root.
+    pub dest: PathBuf,
+    /// Per-mount options.
+    pub options: Vec<String>,
+    /// Optional fields, such as `shared:1` or `master:2`, which define propagation.
+    pub optional_fields: Vec<String>,
+    /// The type of the mounted file system.
+    pub fstype: String,
+    /// The source which is mounted.
+    pub source: PathBuf,
+    /// Per-superblock options.
+    pub super_options: Vec<String>,
+}
+
+impl FromStr for MountInfoEntry {
+    type Err = io::Error;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        Self::parse_numbered(line, 1).map_err(io::Error::from)
+    }
+}
+
+impl MountInfoEntry {
+    /// Parse a mountinfo line, reporting errors at the given `line_number`.
+    pub(crate) fn parse_numbered(
+        line: &str,
+        line_number: usize,
+    ) -> Result<MountInfoEntry, ParseError> {
+        let mut parts = line.split_whitespace();
+
+        let missing = |field| ParseError::MissingField { field, line_number };
+
+        let parse_id = |field, value: &str| {
+            value.parse::<u32>().map_err(|_| ParseError::InvalidNumber {
+                field,
+                line_number,
+                value: value.to_owned(),
+            })
+        };
+
+        let parse_path =
+            |value: &str| MountInfo::parse_value(value, line_number).map(PathBuf::from);
+
+        let mount_id = parts.next().ok_or_else(|| missing("mount ID"))?;
+        let parent_id = parts.next().ok_or_else(|| missing("parent ID"))?;
+        let dev = parts.next().ok_or_else(|| missing("major:minor"))?;
+        let root = parts.next().ok_or_else(|| missing("root"))?;
+        let dest = parts.next().ok_or_else(|| missing("mount point"))?;
+        let options = parts.next().ok_or_else(|| missing("mount options"))?;
+
+        let mut optional_fields = Vec::new();
+        loop {
+            match parts.next() {
+                Some("-") => break,
+                Some(field) => optional_fields.push(field.to_owned()),
+                None => return Err(missing("optional fields separator")),
+            }
+        }
+
+        let fstype = parts.next().ok_or_else(|| missing("type"))?;
+        let source = parts.next().ok_or_else(|| missing("source"))?;
+        let super_options = parts.next().ok_or_else(|| missing("super options"))?;
+
+        // Reused to decode the escapes of each option, and the fstype.
+        let mut buffer = Vec::new();
+
+        let mut dev = dev.splitn(2, ':');
+        let major = dev.next().ok_or_else(|| missing("major"))?;
+        let minor = dev.next().ok_or_else(|| missing("minor"))?;
+
+        Ok(MountInfoEntry {
+            mount_id: parse_id("mount ID", mount_id)?,
+            parent_id: parse_id("parent ID", parent_id)?,
+            dev: (parse_id("major", major)?, parse_id("minor", minor)?),
+            root: parse_path(root)?,
+            dest: parse_path(dest)?,
+            options: MountInfo::parse_options(options, "mount options", line_number, &mut buffer)?,
+            optional_fields,
+            fstype: MountInfo::parse_string(fstype, "type", line_number, &mut buffer)?,
+            source: parse_path(source)?,
+            super_options: MountInfo::parse_options(
+                super_options,
+                "super options",
+                line_number,
+                &mut buffer,
+            )?,
+        })
+    }
+
+    /// The peer group ID if this mount is shared, from the `shared:X` tag.
+    pub fn shared(&self) -> Option<u32> { self.optional_field("shared") }
+
+    /// The peer group ID of the master if this mount is a slave, from the `master:X` tag.
+    pub fn master(&self) -> Option<u32> { self.optional_field("master") }
+
+    /// The closest dominant peer group which this mount receives propagation from, from the
+    /// `propagate_from:X` tag.
+    pub fn propagate_from(&self) -> Option<u32> { self.optional_field("propagate_from") }
+
+    /// Returns true if this is a bind mount, which exposes a subtree of its file system.
+    ///
+    /// This is determined by the `root` not being `/`, so a bind mount of the whole file system
+    /// cannot be distinguished from the original mount.
+    pub fn is_bind(&self) -> bool { self.root != Path::new("/") }
+
+    /// Returns true if this mount is unbindable.
+    pub fn is_unbindable(&self) -> bool {
+        self.optional_fields.iter().any(|field| field == "unbindable")
+    }
+
+    fn optional_field(&self, tag: &str) -> Option<u32> {
+        self.optional_fields.iter().find_map(|field| {
+            let mut parts = field.splitn(2, ':');
+            if parts.next() == Some(tag) {
+                parts.next().and_then(|value| value.parse::<u32>().ok())
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// A list of parsed mount entries from `/proc/self/mountinfo`.
+#[derive(Debug, Default, Clone, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MountInfoList(pub Vec<MountInfoEntry>);
+
+impl MountInfoList {
+    /// Parse mounts given from an iterator of mountinfo lines.
+    pub fn parse_from<'a, I: Iterator<Item = &'a str>>(lines: I) -> io::Result<MountInfoList> {
+        lines
+            .enumerate()
+            .map(|(id, line)| MountInfoEntry::parse_numbered(line, id + 1))
+            .collect::<Result<Vec<MountInfoEntry>, ParseError>>()
+            .map(MountInfoList)
+            .map_err(io::Error::from)
+    }
+
+    /// Read a new list of mounts into memory from `/proc/self/mountinfo`.
+    pub fn from_mountinfo() -> io::Result<MountInfoList> {
+        Self::new_from_file("/proc/self/mountinfo")
+    }
+
+    /// Read a new list of mounts into memory from the mount namespace of the process with the
+    /// given `pid`, from `/proc/<pid>/mountinfo`.
+    ///
+    /// An error is returned if the process does not exist, or if its mounts may not be read.
+    pub fn from_pid_mountinfo(pid: u32) -> io::Result<MountInfoList> {
+        Self::new_from_file(format!("/proc/{}/mountinfo", pid))
+    }
+
+    /// Read a new list of mounts into memory from any mountinfo-like file.
+    pub fn new_from_file<P: AsRef<Path>>(path: P) -> io::Result<MountInfoList> {
+        Self::new_from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Read a new list of mounts into memory from any mountinfo-like reader.
+    pub fn new_from_reader<R: BufRead>(reader: R) -> io::Result<MountInfoList> {
+        reader
+            .lines()
+            .enumerate()
+            .map(|(id, line)| {
+                line.and_then(|line| {
+                    MountInfoEntry::parse_numbered(&line, id + 1).map_err(io::Error::from)
+                })
+            })
+            .collect::<io::Result<Vec<MountInfoEntry>>>()
+            .map(MountInfoList)
+    }
+
+    /// Iterate through each mount which is a bind mount, as determined by `is_bind`.
+    pub fn bind_mounts(&self) -> impl Iterator<Item = &MountInfoEntry> {
+        self.0.iter().filter(|mount| mount.is_bind())
+    }
+
+    /// Find the mount with the given mount ID.
+    pub fn get_mount_by_id(&self, id: u32) -> Option<&MountInfoEntry> {
+        self.0.iter().find(|mount| mount.mount_id == id)
+    }
+
+    /// Find the first mount which has the `path` destination.
+    pub fn get_mount_by_dest<P: AsRef<Path>>(&self, path: P) -> Option<&MountInfoEntry> {
+        self.0.iter().find(|mount| mount.dest == path.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"22 28 0:21 / /sys rw,nosuid,nodev,noexec,relatime shared:7 - sysfs sysfs rw
+23 28 0:22 / /proc rw,nosuid,nodev,noexec,relatime shared:13 - proc proc rw
+28 1 8:2 / / rw,noatime shared:1 - ext4 /dev/sda2 rw,errors=remount-ro
+36 28 8:6 /subvol /mnt/my\040data rw,relatime master:1 propagate_from:2 - ext4 /dev/sda6 rw
+40 28 0:45 / /mnt/private rw,relatime unbindable - tmpfs tmpfs rw,size=1024k"#;
+
+    #[test]
+    fn mountinfo() {
+        let mounts = MountInfoList::parse_from(SAMPLE.lines()).unwrap();
+        assert_eq!(mounts.0.len(), 5);
+
+        assert_eq!(
+            mounts.get_mount_by_id(36).unwrap(),
+            &MountInfoEntry {
+                mount_id:        36,
+                parent_id:       28,
+                dev:             (8, 6),
+                root:            PathBuf::from("/subvol"),
+                dest:            PathBuf::from("/mnt/my data"),
+                options:         vec!["rw".into(), "relatime".into()],
+                optional_fields: vec!["master:1".into(), "propagate_from:2".into()],
+                fstype:          "ext4".into(),
+                source:          PathBuf::from("/dev/sda6"),
+                super_options:   vec!["rw".into()],
+            }
+        );
+
+        let root = mounts.get_mount_by_dest("/").unwrap();
+        assert_eq!(root.shared(), Some(1));
+        assert_eq!(root.master(), None);
+        assert_eq!(root.super_options, vec!["rw".to_owned(), "errors=remount-ro".to_owned()]);
+
+        let data = mounts.get_mount_by_id(36).unwrap();
+        assert_eq!(data.shared(), None);
+        assert_eq!(data.master(), Some(1));
+        assert_eq!(data.propagate_from(), Some(2));
+
+        assert!(mounts.get_mount_by_dest("/mnt/private").unwrap().is_unbindable());
+        assert!(!root.is_unbindable());
+    }
+
+    #[test]
+    fn bind_mounts() {
+        let mounts = MountInfoList::parse_from(SAMPLE.lines()).unwrap();
+        assert_eq!(mounts.bind_mounts().map(|m| m.mount_id).collect::<Vec<_>>(), vec![36]);
+        assert!(mounts.get_mount_by_id(36).unwrap().is_bind());
+        assert!(!mounts.get_mount_by_id(28).unwrap().is_bind());
+    }
+
+    #[test]
+    fn mountinfo_missing_separator() {
+        assert!("28 1 8:2 / / rw shared:1 ext4 /dev/sda2 rw".parse::<MountInfoEntry>().is_err());
+    }
+}
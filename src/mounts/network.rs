@@ -0,0 +1,78 @@
+use super::MountInfo;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The remote location of a network file system's source.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NetworkSource {
+    /// An NFS export, from a source such as `server:/export/path`.
+    Nfs {
+        /// The server, without the brackets of an IPv6 literal.
+        host: String,
+        /// The exported path on the server.
+        export: PathBuf,
+    },
+    /// A CIFS or SMB share, from a source such as `//server/share`.
+    Cifs {
+        /// The server.
+        host: String,
+        /// The share, including any path within it.
+        share: String,
+    },
+}
+
+impl NetworkSource {
+    /// Parse the remote location from the source of an `nfs`, `nfs4`, `cifs`, or `smb3` mount.
+    ///
+    /// Returns `None` for any other file system type, or if the source is not of the expected
+    /// shape.
+    pub fn parse(info: &MountInfo) -> Option<NetworkSource> {
+        let source = info.source.to_str()?;
+        match info.fstype.as_str() {
+            "nfs" | "nfs4" => parse_nfs(source),
+            "cifs" | "smb3" => parse_cifs(source),
+            _ => None,
+        }
+    }
+}
+
+/// Split `host:/export`, where the host may be an IPv6 literal in brackets.
+fn parse_nfs(source: &str) -> Option<NetworkSource> {
+    let (host, export) = if source.starts_with('[') {
+        let end = source.find(']')?;
+        let rest = &source[end + 1..];
+        if !rest.starts_with(':') {
+            return None;
+        }
+
+        (&source[1..end], &rest[1..])
+    } else {
+        let colon = source.find(':')?;
+        (&source[..colon], &source[colon + 1..])
+    };
+
+    if host.is_empty() || export.is_empty() {
+        return None;
+    }
+
+    Some(NetworkSource::Nfs { host: host.into(), export: export.into() })
+}
+
+/// Split `//host/share`.
+fn parse_cifs(source: &str) -> Option<NetworkSource> {
+    if !source.starts_with("//") {
+        return None;
+    }
+
+    let rest = &source[2..];
+    let slash = rest.find('/')?;
+    let (host, share) = (&rest[..slash], &rest[slash + 1..]);
+
+    if host.is_empty() || share.is_empty() {
+        return None;
+    }
+
+    Some(NetworkSource::Cifs { host: host.into(), share: share.into() })
+}
@@ -0,0 +1,79 @@
+use super::MountInfo;
+use std::{
+    ffi::{OsStr, OsString},
+    io,
+    os::unix::ffi::OsStrExt,
+    str,
+};
+use ParseError;
+
+/// Escape a path for a mount tab, such as `/etc/fstab`, the way the kernel writes it in
+/// `/proc/mounts`.
+///
+/// Spaces, tabs, newlines, and backslashes are written as the octal sequences `\040`, `\011`,
+/// `\012`, and `\134`. Bytes which are not valid UTF-8 are also written as octal sequences, so
+/// that `decode_octal` restores the original path.
+///
+/// ```rust
+/// extern crate proc_mounts;
+///
+/// use proc_mounts::{decode_octal, encode_octal};
+/// use std::ffi::OsStr;
+///
+/// let encoded = encode_octal(OsStr::new("/media/My Disk"));
+/// assert_eq!(encoded, "/media/My\\040Disk");
+/// assert_eq!(decode_octal(&encoded).unwrap(), "/media/My Disk");
+/// ```
+pub fn encode_octal(path: &OsStr) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    let mut bytes = path.as_bytes();
+
+    loop {
+        let (valid, invalid) = match str::from_utf8(bytes) {
+            Ok(valid) => (valid, &[][..]),
+            Err(why) => {
+                let (valid, rest) = bytes.split_at(why.valid_up_to());
+                let invalid_len = why.error_len().unwrap_or_else(|| rest.len());
+                bytes = &rest[invalid_len..];
+                (str::from_utf8(valid).unwrap(), &rest[..invalid_len])
+            }
+        };
+
+        for c in valid.chars() {
+            match c {
+                ' ' => encoded.push_str("\\040"),
+                '\t' => encoded.push_str("\\011"),
+                '\n' => encoded.push_str("\\012"),
+                '\\' => encoded.push_str("\\134"),
+                c => encoded.push(c),
+            }
+        }
+
+        if invalid.is_empty() {
+            return encoded;
+        }
+
+        for byte in invalid {
+            encoded.push_str(&format!("\\{:03o}", byte));
+        }
+    }
+}
+
+/// Decode the octal sequences of a value from a mount tab, such as `\040` for a space.
+///
+/// This is the inverse of `encode_octal`. An error of kind `InvalidData` is returned if a
+/// backslash is not followed by three octal digits of at most `\377`.
+pub fn decode_octal(value: &str) -> io::Result<OsString> {
+    // The value is not from a line of a mount tab, so the error omits the line number.
+    MountInfo::parse_value(value, 0).map_err(|why| {
+        let message = match why {
+            ParseError::TruncatedOctal { .. } => "truncated octal code".to_owned(),
+            ParseError::InvalidOctal { sequence, .. } => {
+                format!("invalid octal code: {}", sequence)
+            }
+            why => why.to_string(),
+        };
+
+        io::Error::new(io::ErrorKind::InvalidData, message)
+    })
+}
@@ -0,0 +1,62 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A typed view of the common options that a file system may be mounted with.
+///
+/// Options are applied in order, so when conflicting options are given, such as `ro` and `rw`,
+/// the last one wins.
+#[derive(Debug, Default, Clone, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MountOptions {
+    /// Mounted read-only, via `ro`.
+    pub read_only: bool,
+    /// Execution of binaries is disallowed, via `noexec`.
+    pub no_exec: bool,
+    /// Set-user-ID and set-group-ID bits are ignored, via `nosuid`.
+    pub no_suid: bool,
+    /// Device files are not interpreted, via `nodev`.
+    pub no_dev: bool,
+    /// Access times are updated relative to modify or change times, via `relatime`.
+    pub relatime: bool,
+    /// Access times are not updated, via `noatime`.
+    pub noatime: bool,
+    /// Every option which is not represented by the fields above, in order.
+    pub extra: Vec<String>,
+}
+
+impl MountOptions {
+    /// Parse a list of mount options, such as those from `MountInfo::options`.
+    pub fn parse<S: AsRef<str>>(options: &[S]) -> MountOptions {
+        let mut parsed = MountOptions::default();
+
+        for option in options {
+            match option.as_ref() {
+                "ro" => parsed.read_only = true,
+                "rw" => parsed.read_only = false,
+                "noexec" => parsed.no_exec = true,
+                "exec" => parsed.no_exec = false,
+                "nosuid" => parsed.no_suid = true,
+                "suid" => parsed.no_suid = false,
+                "nodev" => parsed.no_dev = true,
+                "dev" => parsed.no_dev = false,
+                "relatime" => {
+                    parsed.relatime = true;
+                    parsed.noatime = false;
+                }
+                "norelatime" => parsed.relatime = false,
+                "noatime" => {
+                    parsed.noatime = true;
+                    parsed.relatime = false;
+                }
+                "atime" => parsed.noatime = false,
+                "strictatime" => {
+                    parsed.relatime = false;
+                    parsed.noatime = false;
+                }
+                option => parsed.extra.push(option.to_owned()),
+            }
+        }
+
+        parsed
+    }
+}
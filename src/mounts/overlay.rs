@@ -0,0 +1,56 @@
+use super::MountInfo;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{iter, path::PathBuf};
+
+/// The directories which are combined by an overlay file system.
+#[derive(Debug, Default, Clone, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OverlayPaths {
+    /// The read-only lower layers, from the uppermost to the lowest, via `lowerdir`.
+    pub lowerdir: Vec<PathBuf>,
+    /// The writable upper layer, via `upperdir`, which is absent for read-only overlays.
+    pub upperdir: Option<PathBuf>,
+    /// The work directory of the upper layer, via `workdir`.
+    pub workdir: Option<PathBuf>,
+}
+
+impl OverlayPaths {
+    /// Parse the overlay directories from the options of an overlay mount.
+    pub fn parse(info: &MountInfo) -> OverlayPaths {
+        OverlayPaths {
+            lowerdir: info
+                .option_value("lowerdir")
+                .map_or_else(Vec::new, |value| split_unescaped(value).map(PathBuf::from).collect()),
+            upperdir: info.option_value("upperdir").map(PathBuf::from),
+            workdir: info.option_value("workdir").map(PathBuf::from),
+        }
+    }
+}
+
+/// Split a `lowerdir` value on each `:` which is not escaped as `\:`.
+fn split_unescaped<'a>(value: &'a str) -> impl Iterator<Item = String> + 'a {
+    let mut chars = value.chars();
+    let mut done = false;
+
+    iter::from_fn(move || {
+        if done {
+            return None;
+        }
+
+        let mut path = String::new();
+        loop {
+            match chars.next() {
+                Some('\\') => path.extend(chars.next()),
+                Some(':') => break,
+                Some(c) => path.push(c),
+                None => {
+                    done = true;
+                    break;
+                }
+            }
+        }
+
+        Some(path)
+    })
+}
@@ -0,0 +1,45 @@
+use super::{MountInfo, MountList};
+use std::{collections::HashSet, path::Path};
+
+/// How the entries of `/etc/fstab` compare to the live mounts, with entries matched by their
+/// destination.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Reconciliation<'a> {
+    /// Entries of the fstab whose destination is mounted.
+    pub mounted: Vec<&'a MountInfo>,
+    /// Entries of the fstab whose destination is not mounted.
+    pub missing: Vec<&'a MountInfo>,
+    /// Live mounts whose destination is not in the fstab.
+    pub extra: Vec<&'a MountInfo>,
+}
+
+impl<'a> Reconciliation<'a> {
+    /// Compare the `live` mounts to the entries of the `fstab`.
+    ///
+    /// Swap entries of the fstab are ignored, as they have no destination to mount to.
+    pub fn new(live: &'a MountList, fstab: &'a MountList) -> Reconciliation<'a> {
+        fn dests(list: &MountList) -> HashSet<&Path> {
+            list.0.iter().map(|mount| mount.dest.as_path()).collect()
+        }
+
+        let live_dests = dests(live);
+        let fstab_dests = dests(fstab);
+        let mut reconciliation = Reconciliation::default();
+
+        for entry in fstab.0.iter().filter(|entry| entry.fstype != "swap") {
+            if live_dests.contains(entry.dest.as_path()) {
+                reconciliation.mounted.push(entry);
+            } else {
+                reconciliation.missing.push(entry);
+            }
+        }
+
+        for mount in &live.0 {
+            if !fstab_dests.contains(mount.dest.as_path()) {
+                reconciliation.extra.push(mount);
+            }
+        }
+
+        reconciliation
+    }
+}
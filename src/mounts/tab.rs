@@ -99,14 +99,14 @@ impl FromStr for MountTab {
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         let mut entries = Vec::new();
 
-        for line in input.lines() {
+        for (id, line) in input.lines().enumerate() {
             let line = line.trim_start();
             if line.is_empty() {
                 entries.push(AbstractMountElement::Empty);
             } else if line.starts_with('#') {
                 entries.push(AbstractMountElement::Comment(line.to_owned()));
             } else {
-                let info = line.parse::<MountInfo>()?;
+                let info = MountInfo::parse_numbered(line, id + 1)?;
                 entries.push(AbstractMountElement::Mount(info));
             }
         }
@@ -0,0 +1,29 @@
+use std::{io, sync::mpsc, thread, time::Duration};
+
+/// Run `func` on a helper thread, waiting up to `timeout` for its result.
+///
+/// If the time runs out, an error of kind `TimedOut` is returned, and the helper thread is left
+/// detached, as a thread blocked in a system call cannot be cancelled. It exits once the call
+/// returns, if ever.
+pub(crate) fn with_timeout<T, F>(timeout: Duration, func: F) -> io::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> io::Result<T> + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    thread::Builder::new().name("proc-mounts-timeout".into()).spawn(move || {
+        // The receiver is gone if the caller has already timed out.
+        let _ = sender.send(func());
+    })?;
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("operation did not complete within {:?}", timeout),
+        )),
+        Err(mpsc::RecvTimeoutError::Disconnected) => {
+            Err(io::Error::new(io::ErrorKind::Other, "operation panicked"))
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use super::{MountInfo, MountList};
+use std::path::Path;
+
+/// A mount within the hierarchy of mounts, along with the mounts nested beneath it.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct MountNode<'a> {
+    /// The mount at this node, which is `None` for a root without a mount at `/`.
+    pub mount: Option<&'a MountInfo>,
+    /// Mounts whose nearest mounted ancestor is this mount, sorted by destination depth.
+    pub children: Vec<MountNode<'a>>,
+}
+
+impl<'a> MountNode<'a> {
+    /// Build the hierarchy of mounts, rooted at `/`.
+    ///
+    /// Each mount becomes a child of the mount with the longest destination that contains it.
+    /// Where a destination is mounted over, the later mount is nested beneath the earlier one.
+    pub fn new(list: &'a MountList) -> MountNode<'a> {
+        let mut mounts = list.0.iter().collect::<Vec<_>>();
+        mounts.sort_by_key(|mount| mount.dest.components().count());
+
+        let mut root = MountNode::default();
+        for mount in mounts {
+            if root.mount.is_none() && mount.dest == Path::new("/") {
+                root.mount = Some(mount);
+            } else {
+                root.insert(mount);
+            }
+        }
+
+        root
+    }
+
+    fn insert(&mut self, mount: &'a MountInfo) {
+        let parent = self.children.iter_mut().rev().find(|child| match child.mount {
+            Some(parent) => mount.dest.starts_with(&parent.dest),
+            None => false,
+        });
+
+        match parent {
+            Some(parent) => parent.insert(mount),
+            None => self.children.push(MountNode { mount: Some(mount), children: Vec::new() }),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use super::{timeout::with_timeout, MountInfo};
+use libc;
+use std::{ffi::CString, io, mem, os::unix::ffi::OsStrExt, time::Duration};
+
+/// Space and inode usage of a mounted file system, as reported by `statvfs`.
+#[derive(Debug, Default, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct FsUsage {
+    /// The size of the file system, in bytes.
+    pub total_bytes:     u64,
+    /// The number of free bytes, including those reserved for the superuser.
+    pub free_bytes:      u64,
+    /// The number of free bytes which are available to unprivileged users.
+    pub available_bytes: u64,
+    /// The number of inodes in the file system.
+    pub inodes_total:    u64,
+    /// The number of free inodes.
+    pub inodes_free:     u64,
+}
+
+impl MountInfo {
+    /// Fetch the space and inode usage of the file system mounted at `dest`.
+    ///
+    /// An error is returned if the mount has since been removed, or is otherwise inaccessible.
+    ///
+    /// ```rust,no_run
+    /// extern crate proc_mounts;
+    ///
+    /// use proc_mounts::MountList;
+    /// use std::io;
+    ///
+    /// fn main() -> io::Result<()> {
+    ///     let mounts = MountList::new()?;
+    ///     if let Some(root) = mounts.get_mount_by_dest("/") {
+    ///         let usage = root.usage()?;
+    ///         println!("{} of {} bytes available", usage.available_bytes, usage.total_bytes);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn usage(&self) -> io::Result<FsUsage> {
+        let path = CString::new(self.dest.as_os_str().as_bytes())?;
+
+        let mut stat: libc::statvfs = unsafe { mem::zeroed() };
+        if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let fragment_size = stat.f_frsize as u64;
+        Ok(FsUsage {
+            total_bytes:     stat.f_blocks as u64 * fragment_size,
+            free_bytes:      stat.f_bfree as u64 * fragment_size,
+            available_bytes: stat.f_bavail as u64 * fragment_size,
+            inodes_total:    stat.f_files as u64,
+            inodes_free:     stat.f_ffree as u64,
+        })
+    }
+
+    /// Fetch the usage as with `usage`, giving up with an error of kind `TimedOut` if the
+    /// `statvfs` call does not complete within `timeout`.
+    ///
+    /// A `statvfs` on a network file system whose server is unreachable may block indefinitely,
+    /// so monitoring agents should prefer this. Each call spawns a thread for the `statvfs`. On
+    /// a timeout, that thread stays blocked in the call, and so one thread is leaked for as long
+    /// as the server is unreachable.
+    pub fn usage_timeout(&self, timeout: Duration) -> io::Result<FsUsage> {
+        let mount = self.clone();
+        with_timeout(timeout, move || mount.usage())
+    }
+}
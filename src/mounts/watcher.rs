@@ -0,0 +1,51 @@
+use super::MountList;
+use libc;
+use std::{
+    fs::File,
+    io::{self, BufReader, Seek, SeekFrom},
+    os::unix::io::AsRawFd,
+    path::Path,
+};
+
+/// Waits for changes to the mount table, returning the new list of mounts on each change.
+///
+/// The kernel does not emit inotify events for `/proc/mounts`. Instead, an open handle to the
+/// file becomes readable with an exceptional condition (`POLLPRI`) whenever a file system is
+/// mounted or unmounted in the namespace, which is what this type waits on with `poll()`.
+pub struct MountWatcher {
+    file: File,
+}
+
+impl MountWatcher {
+    /// Watch `/proc/mounts` for changes.
+    pub fn new() -> io::Result<Self> { Self::new_from_file("/proc/mounts") }
+
+    /// Watch any procfs mounts file, such as `/proc/<pid>/mounts`, for changes.
+    pub fn new_from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self { file: File::open(path)? })
+    }
+
+    /// Block until the mount table changes, then return the freshly parsed list of mounts.
+    pub fn wait(&mut self) -> io::Result<MountList> {
+        let mut pollfd =
+            libc::pollfd { fd: self.file.as_raw_fd(), events: libc::POLLPRI, revents: 0 };
+
+        loop {
+            if unsafe { libc::poll(&mut pollfd, 1, -1) } == -1 {
+                let why = io::Error::last_os_error();
+                if why.kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+
+                return Err(why);
+            }
+
+            if pollfd.revents & (libc::POLLPRI | libc::POLLERR) != 0 {
+                break;
+            }
+        }
+
+        self.file.seek(SeekFrom::Start(0))?;
+        MountList::new_from_reader(BufReader::new(&mut self.file))
+    }
+}
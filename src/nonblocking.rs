@@ -0,0 +1,64 @@
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::fs;
+use MountList;
+use SwapList;
+
+/// A future which reads a file into a string without blocking, then parses it.
+///
+/// Parsing is cheap enough that it is done synchronously once the contents have been read.
+struct ReadAndParse<T> {
+    read:  Pin<Box<dyn Future<Output = io::Result<String>> + Send>>,
+    parse: fn(String) -> io::Result<T>,
+}
+
+impl<T> ReadAndParse<T> {
+    fn new(path: &'static str, parse: fn(String) -> io::Result<T>) -> Self {
+        Self { read: Box::pin(fs::read_to_string(path)), parse }
+    }
+}
+
+impl<T> Future for ReadAndParse<T> {
+    type Output = io::Result<T>;
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<Self::Output> {
+        let parse = self.parse;
+        self.read.as_mut().poll(context).map(|result| result.and_then(parse))
+    }
+}
+
+impl MountList {
+    /// Read a new list of mounts into memory from `/proc/mounts`, without blocking the runtime.
+    pub fn new_async() -> impl Future<Output = io::Result<MountList>> + Send {
+        ReadAndParse::new("/proc/mounts", MountList::parse_owned)
+    }
+}
+
+impl SwapList {
+    /// Read a new list of swaps into memory from `/proc/swaps`, without blocking the runtime.
+    pub fn new_async() -> impl Future<Output = io::Result<SwapList>> + Send {
+        ReadAndParse::new("/proc/swaps", |contents| SwapList::new_from_reader(contents.as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::runtime;
+
+    #[test]
+    fn new_async() {
+        let mut runtime = runtime::Builder::new().basic_scheduler().build().unwrap();
+
+        let mounts = runtime.block_on(MountList::new_async()).unwrap();
+        assert!(!mounts.is_empty());
+        assert!(MountList::new().is_ok());
+
+        let swaps = runtime.block_on(SwapList::new_async());
+        assert_eq!(swaps.is_ok(), SwapList::new().is_ok());
+    }
+}
@@ -1,25 +1,63 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::{
-    char,
-    ffi::OsString,
+    cmp::Reverse,
+    collections::HashMap,
+    convert::TryFrom,
+    ffi::{OsStr, OsString},
     fmt::{self, Display, Formatter},
     fs::File,
-    io::{self, BufRead, BufReader, Error, ErrorKind},
-    os::unix::ffi::OsStringExt,
+    io::{self, BufRead, BufReader, Read},
+    ops::Deref,
     path::{Path, PathBuf},
+    slice,
     str::FromStr,
+    thread,
+    time::Duration,
+    vec,
 };
+use MountInfo;
+use ParseError;
+
+/// The kind of a swap, as given by the `Type` column of `/proc/swaps`.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SwapKind {
+    /// A swap partition on a block device.
+    Partition,
+    /// A swap file stored within a file system.
+    File,
+    /// Any other kind of swap, which is kept as it was given.
+    Other(OsString),
+}
+
+impl<'a> From<&'a OsStr> for SwapKind {
+    fn from(kind: &'a OsStr) -> Self {
+        if kind == "partition" {
+            SwapKind::Partition
+        } else if kind == "file" {
+            SwapKind::File
+        } else {
+            SwapKind::Other(kind.to_owned())
+        }
+    }
+}
 
 /// A swap entry, which defines an active swap.
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SwapInfo {
     /// The path where the swap originates from.
-    pub source: PathBuf,
+    pub source:   PathBuf,
     /// The kind of swap, such as `partition` or `file`.
-    pub kind: OsString,
-    /// The size of the swap partition.
-    pub size: usize,
-    /// Whether the swap is used or not.
-    pub used: usize,
+    pub kind:     OsString,
+    /// The size of the swap partition, in kibibytes.
+    ///
+    /// When parsing, the size and usage may be plain integers, as the kernel writes them, or
+    /// have a `kB` or `K` suffix.
+    pub size:     usize,
+    /// How much of the swap is used, in kibibytes.
+    pub used:     usize,
     /// The priority of a swap, which indicates the order of usage.
     pub priority: isize,
 }
@@ -42,84 +80,217 @@ impl FromStr for SwapInfo {
     type Err = io::Error;
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
+        Self::parse_numbered(line, 1).map_err(io::Error::from)
+    }
+}
+
+/// Parse a single `/proc/swaps`-like line, as with `FromStr`.
+///
+/// ```rust
+/// extern crate proc_mounts;
+///
+/// use proc_mounts::SwapInfo;
+/// use std::{convert::TryInto, io};
+///
+/// fn main() -> io::Result<()> {
+///     let swap: SwapInfo = "/dev/sda5 partition 8388600 0 -2".try_into()?;
+///     assert_eq!(swap.priority, -2);
+///     Ok(())
+/// }
+/// ```
+impl<'a> TryFrom<&'a str> for SwapInfo {
+    type Error = io::Error;
+
+    fn try_from(line: &'a str) -> Result<Self, Self::Error> { line.parse::<Self>() }
+}
+
+impl SwapInfo {
+    // Attempt to parse a `/proc/swaps`-like line.
+    #[deprecated]
+    pub fn parse_line(line: &str) -> io::Result<SwapInfo> { line.parse::<Self>() }
+
+    /// The kind of swap, parsed into a `SwapKind`.
+    pub fn kind_parsed(&self) -> SwapKind { SwapKind::from(self.kind.as_os_str()) }
+
+    /// Returns true if this swap is a swap file.
+    pub fn is_file(&self) -> bool { self.kind == "file" }
+
+    /// Returns true if this swap is a swap partition.
+    pub fn is_partition(&self) -> bool { self.kind == "partition" }
+
+    /// Returns true if the priority was assigned by the kernel, which gives negative priorities
+    /// to swaps that were enabled without one.
+    pub fn priority_is_auto(&self) -> bool { self.priority < 0 }
+
+    /// Returns true if the priority was given explicitly, such as with `swapon -p`.
+    pub fn is_user_priority(&self) -> bool { !self.priority_is_auto() }
+
+    /// The percentage of the swap which is used, or `0.0` if the swap has no size.
+    pub fn used_percent(&self) -> f64 { percent(self.used, self.size) }
+
+    /// The size of the swap, formatted for display, such as `8.0 GiB`. See `format_kib`.
+    pub fn size_human(&self) -> String { format_kib(self.size) }
+
+    /// The usage of the swap, formatted for display, such as `512.0 MiB`. See `format_kib`.
+    pub fn used_human(&self) -> String { format_kib(self.used) }
+
+    /// Parse a `/proc/swaps`-like line, reporting errors at the given `line_number`.
+    pub(crate) fn parse_numbered(line: &str, line_number: usize) -> Result<SwapInfo, ParseError> {
         let mut parts = line.split_whitespace();
 
-        fn parse<F: FromStr>(string: &OsString) -> io::Result<F> {
-            let string = string.to_str().ok_or_else(|| {
-                Error::new(ErrorKind::InvalidData, "/proc/swaps contains non-UTF8 entry")
-            })?;
+        fn parse<F: FromStr>(
+            field: &'static str,
+            string: &OsString,
+            line_number: usize,
+        ) -> Result<F, ParseError> {
+            let string = string.to_str().ok_or(ParseError::NonUtf8 { field, line_number })?;
 
-            string.parse::<F>().map_err(|_| {
-                Error::new(ErrorKind::InvalidData, "/proc/swaps contains invalid data")
+            string.parse::<F>().map_err(|_| ParseError::InvalidNumber {
+                field,
+                line_number,
+                value: string.to_owned(),
+            })
+        }
+
+        // The kernel writes sizes as plain kibibytes, but some tools add a `kB` or `K` suffix.
+        fn parse_kib(
+            field: &'static str,
+            string: &OsString,
+            line_number: usize,
+        ) -> Result<usize, ParseError> {
+            let string = string.to_str().ok_or(ParseError::NonUtf8 { field, line_number })?;
+            let number = if string.ends_with("kB") {
+                &string[..string.len() - 2]
+            } else if string.ends_with('K') {
+                &string[..string.len() - 1]
+            } else {
+                string
+            };
+
+            number.parse::<usize>().map_err(|_| ParseError::InvalidNumber {
+                field,
+                line_number,
+                value: string.to_owned(),
             })
         }
 
         macro_rules! next_value {
-            ($err:expr) => {{
+            ($field:expr) => {{
                 parts
                     .next()
-                    .ok_or_else(|| Error::new(ErrorKind::Other, $err))
-                    .and_then(|val| Self::parse_value(val))
+                    .ok_or(ParseError::MissingField { field: $field, line_number })
+                    .and_then(|val| MountInfo::parse_value(val, line_number))
             }};
         }
 
         Ok(SwapInfo {
-            source:   PathBuf::from(next_value!("Missing source")?),
-            kind:     next_value!("Missing kind")?,
-            size:     parse::<usize>(&next_value!("Missing size")?)?,
-            used:     parse::<usize>(&next_value!("Missing used")?)?,
-            priority: parse::<isize>(&next_value!("Missing priority")?)?,
+            source:   PathBuf::from(next_value!("source")?),
+            kind:     next_value!("kind")?,
+            size:     parse_kib("size", &next_value!("size")?, line_number)?,
+            used:     parse_kib("used", &next_value!("used")?, line_number)?,
+            priority: parse::<isize>("priority", &next_value!("priority")?, line_number)?,
         })
     }
 }
 
-impl SwapInfo {
-    // Attempt to parse a `/proc/swaps`-like line.
-    #[deprecated]
-    pub fn parse_line(line: &str) -> io::Result<SwapInfo> { line.parse::<Self>() }
+/// Format a quantity of kibibytes for display, scaled to the largest unit of `KiB`, `MiB`,
+/// `GiB`, or `TiB` in which it is at least one, with one decimal place.
+///
+/// ```rust
+/// extern crate proc_mounts;
+///
+/// use proc_mounts::format_kib;
+///
+/// assert_eq!(format_kib(8_388_600), "8.0 GiB");
+/// assert_eq!(format_kib(1536), "1.5 MiB");
+/// assert_eq!(format_kib(0), "0.0 KiB");
+/// ```
+pub fn format_kib(kib: usize) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB"];
 
-    fn parse_value(value: &str) -> io::Result<OsString> {
-        let mut ret = Vec::new();
-
-        let mut bytes = value.bytes();
-        while let Some(b) = bytes.next() {
-            match b {
-                b'\\' => {
-                    let mut code = 0;
-                    for _i in 0..3 {
-                        if let Some(b) = bytes.next() {
-                            code *= 8;
-                            code += u32::from_str_radix(&(b as char).to_string(), 8)
-                                .map_err(|err| Error::new(ErrorKind::Other, err))?;
-                        } else {
-                            return Err(Error::new(ErrorKind::Other, "truncated octal code"));
-                        }
-                    }
-                    ret.push(code as u8);
-                }
-                _ => {
-                    ret.push(b);
-                }
-            }
-        }
+    let mut value = kib as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
 
-        Ok(OsString::from_vec(ret))
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Returns true if the line is the header of `/proc/swaps`, which names each column.
+fn is_header(line: &str) -> bool { line.trim_start().starts_with("Filename") }
+
+fn is_blank(line: &str) -> bool { line.trim().is_empty() }
+
+fn percent(used: usize, size: usize) -> f64 {
+    if size == 0 {
+        0.0
+    } else {
+        used as f64 / size as f64 * 100.0
     }
 }
 
 /// A list of parsed swap entries from `/proc/swaps`.
+///
+/// The list dereferences to a slice of its entries, and may be iterated directly.
+///
+/// ```rust
+/// extern crate proc_mounts;
+///
+/// use proc_mounts::SwapList;
+///
+/// let list = SwapList::parse_from("/dev/sda5 partition 8388600 0 -2".lines()).unwrap();
+///
+/// assert_eq!(list.len(), 1);
+///
+/// for swap in &list {
+///     println!("{} has priority {}", swap.source.display(), swap.priority);
+/// }
+/// ```
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SwapList(pub Vec<SwapInfo>);
 
 impl SwapList {
     pub fn parse_from<'a, I: Iterator<Item = &'a str>>(lines: I) -> io::Result<SwapList> {
-        lines.map(SwapInfo::from_str).collect::<io::Result<Vec<SwapInfo>>>().map(SwapList)
+        lines
+            .enumerate()
+            .map(|(id, line)| SwapInfo::parse_numbered(line, id + 1))
+            .collect::<Result<Vec<SwapInfo>, ParseError>>()
+            .map(SwapList)
+            .map_err(io::Error::from)
     }
 
-    pub fn new() -> io::Result<SwapList> {
-        Ok(SwapList(SwapIter::new()?.collect::<io::Result<Vec<SwapInfo>>>()?))
+    /// Parse swaps given from an iterator of `/proc/swaps` lines, which may begin with the
+    /// header line.
+    ///
+    /// Unlike `parse_from`, blank lines are skipped, and the first remaining line is skipped if it
+    /// is the `Filename ...` header, and is otherwise parsed as an entry. Line numbers in errors
+    /// count the header and blank lines when present.
+    pub fn parse_with_header<'a, I: Iterator<Item = &'a str>>(lines: I) -> io::Result<SwapList> {
+        let mut lines = lines.enumerate().filter(|&(_, line)| !is_blank(line)).peekable();
+        if lines.peek().map_or(false, |&(_, line)| is_header(line)) {
+            lines.next();
+        }
+
+        lines
+            .map(|(id, line)| SwapInfo::parse_numbered(line, id + 1))
+            .collect::<Result<Vec<SwapInfo>, ParseError>>()
+            .map(SwapList)
+            .map_err(io::Error::from)
     }
 
+    /// Create a list from swap entries which are already in memory.
+    pub fn from_entries(entries: Vec<SwapInfo>) -> SwapList { SwapList(entries) }
+
+    pub fn new() -> io::Result<SwapList> { Self::from_file("/proc/swaps") }
+
+    /// Read swaps from the given path.
+    ///
+    /// This is equivalent to `new_from_file`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<SwapList> { Self::new_from_file(path) }
+
     pub fn new_from_file<P: AsRef<Path>>(path: P) -> io::Result<SwapList> {
         Ok(SwapList(SwapIter::new_from_file(path)?.collect::<io::Result<Vec<SwapInfo>>>()?))
     }
@@ -128,16 +299,211 @@ impl SwapList {
         Ok(SwapList(SwapIter::new_from_reader(reader)?.collect::<io::Result<Vec<SwapInfo>>>()?))
     }
 
+    /// Read swaps from any unbuffered source, such as a fixture or a captured snapshot of
+    /// `/proc/swaps`. The first line is skipped if it is the header.
+    pub fn parse_reader<R: Read>(reader: R) -> io::Result<SwapList> {
+        Self::new_from_reader(BufReader::new(reader))
+    }
+
+    /// The number of swaps in the list.
+    #[must_use]
+    pub fn len(&self) -> usize { self.0.len() }
+
+    /// Returns true if the list contains no swaps.
+    #[must_use]
+    pub fn is_empty(&self) -> bool { self.0.is_empty() }
+
     /// Returns true if the given path is a entry in the swap list.
-    pub fn get_swapped(&self, path: &Path) -> bool {
-        self.0.iter().any(|mount| mount.source == path)
+    pub fn get_swapped(&self, path: &Path) -> bool { self.get_swap(path).is_some() }
+
+    /// Find the swap entry whose source is the given `path`.
+    pub fn get_swap<P: AsRef<Path>>(&self, path: P) -> Option<&SwapInfo> {
+        self.0.iter().find(|swap| swap.source == path.as_ref())
+    }
+
+    /// Iterate through each swap file.
+    pub fn files(&self) -> impl Iterator<Item = &SwapInfo> {
+        self.0.iter().filter(|swap| swap.is_file())
+    }
+
+    /// Iterate through each swap partition.
+    pub fn partitions(&self) -> impl Iterator<Item = &SwapInfo> {
+        self.0.iter().filter(|swap| swap.is_partition())
+    }
+
+    /// Swaps in the order the kernel allocates from them, which is by descending priority.
+    ///
+    /// Swaps with equal priority retain the order in which they were listed.
+    pub fn by_priority(&self) -> Vec<&SwapInfo> {
+        let mut swaps = self.0.iter().collect::<Vec<_>>();
+        swaps.sort_by_key(|swap| Reverse(swap.priority));
+        swaps
+    }
+
+    /// Swaps ordered by their free space, from the most free to the least.
+    ///
+    /// Swaps with equal free space retain the order in which they were listed.
+    pub fn by_free_space(&self) -> Vec<&SwapInfo> {
+        let mut swaps = self.0.iter().collect::<Vec<_>>();
+        swaps.sort_by_key(|swap| Reverse(swap.size.saturating_sub(swap.used)));
+        swaps
+    }
+
+    /// The combined size of all swaps, in kibibytes.
+    pub fn total_size(&self) -> usize {
+        self.0.iter().fold(0, |total, swap| total.saturating_add(swap.size))
+    }
+
+    /// The combined usage of all swaps, in kibibytes.
+    pub fn total_used(&self) -> usize {
+        self.0.iter().fold(0, |total, swap| total.saturating_add(swap.used))
+    }
+
+    /// The combined free space of all swaps, in kibibytes.
+    pub fn total_free(&self) -> usize { self.total_size().saturating_sub(self.total_used()) }
+
+    /// The percentage of all swap space which is used, or `0.0` if there is no swap space.
+    pub fn total_used_percent(&self) -> f64 { percent(self.total_used(), self.total_size()) }
+
+    /// Returns true if more than `threshold_percent` of all swap space is used, where the
+    /// threshold is a percentage from `0.0` to `100.0`, as returned by `total_used_percent`.
+    ///
+    /// Note that the threshold is not a fraction: `0.8` means 0.8%, not 80%, so pass `80.0` for
+    /// the latter.
+    ///
+    /// Without any swap space, there is no swap pressure, and so false is returned.
+    pub fn is_nearly_full(&self, threshold_percent: f64) -> bool {
+        debug_assert!(threshold_percent >= 0.0 && threshold_percent <= 100.0);
+        self.total_size() != 0 && self.total_used_percent() > threshold_percent
+    }
+
+    /// Compare this list of swaps to a `new` list, matching swaps by their source.
+    pub fn diff<'a>(&'a self, new: &'a SwapList) -> SwapDiff<'a> { SwapDiff::new(self, new) }
+}
+
+impl Deref for SwapList {
+    type Target = [SwapInfo];
+
+    fn deref(&self) -> &Self::Target { &self.0 }
+}
+
+impl IntoIterator for SwapList {
+    type IntoIter = vec::IntoIter<SwapInfo>;
+    type Item = SwapInfo;
+
+    fn into_iter(self) -> Self::IntoIter { self.0.into_iter() }
+}
+
+impl<'a> IntoIterator for &'a SwapList {
+    type IntoIter = slice::Iter<'a, SwapInfo>;
+    type Item = &'a SwapInfo;
+
+    fn into_iter(self) -> Self::IntoIter { self.0.iter() }
+}
+
+/// The differences between two lists of swaps, with swaps matched by their source.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct SwapDiff<'a> {
+    /// Swaps whose source only exists in the new list.
+    pub added:   Vec<&'a SwapInfo>,
+    /// Swaps whose source only exists in the old list.
+    pub removed: Vec<&'a SwapInfo>,
+    /// Swaps whose source exists in both lists, but which differ, as `(old, new)` pairs.
+    ///
+    /// As usage is part of each entry, a swap which is merely paged into is listed here.
+    pub changed: Vec<(&'a SwapInfo, &'a SwapInfo)>,
+}
+
+impl<'a> SwapDiff<'a> {
+    /// Compare the `old` list of swaps to the `new` list.
+    pub fn new(old: &'a SwapList, new: &'a SwapList) -> SwapDiff<'a> {
+        fn by_source(list: &SwapList) -> HashMap<&Path, &SwapInfo> {
+            list.0.iter().map(|swap| (swap.source.as_path(), swap)).collect()
+        }
+
+        let old_sources = by_source(old);
+        let new_sources = by_source(new);
+        let mut diff = SwapDiff::default();
+
+        for swap in &old.0 {
+            if !new_sources.contains_key(swap.source.as_path()) {
+                diff.removed.push(swap);
+            }
+        }
+
+        for swap in &new.0 {
+            match old_sources.get(swap.source.as_path()) {
+                Some(&old_swap) => {
+                    if old_swap != swap {
+                        diff.changed.push((old_swap, swap));
+                    }
+                }
+                None => diff.added.push(swap),
+            }
+        }
+
+        diff
+    }
+
+    /// Returns true if there are no differences.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Waits for changes to the active swaps, returning the new list of swaps on each change.
+///
+/// Unlike `/proc/mounts`, `/proc/swaps` does not signal changes with `POLLPRI`, so this type
+/// polls: it sleeps for the given interval, reparses the file, and compares the result to the
+/// last list that it saw. A change is therefore noticed up to one interval late, and a swap
+/// which is added and removed again within one interval is not noticed at all. As the usage of
+/// each swap is part of its entry, paging activity is also reported as a change.
+pub struct SwapWatcher {
+    path:     PathBuf,
+    interval: Duration,
+    last:     SwapList,
+}
+
+impl SwapWatcher {
+    /// Watch `/proc/swaps` for changes, polling it on every `interval`.
+    pub fn new(interval: Duration) -> io::Result<Self> {
+        Self::new_from_file("/proc/swaps", interval)
+    }
+
+    /// Watch any swaps file for changes, polling it on every `interval`.
+    pub fn new_from_file<P: AsRef<Path>>(path: P, interval: Duration) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let last = SwapList::new_from_file(&path)?;
+        Ok(Self { path, interval, last })
+    }
+
+    /// The list of swaps as of the last poll.
+    pub fn swaps(&self) -> &SwapList { &self.last }
+
+    /// Block until the list of swaps differs from the last one seen, then return the new list.
+    ///
+    /// Use `SwapList::diff` against the previous list to see which swaps were added or
+    /// removed.
+    pub fn wait(&mut self) -> io::Result<SwapList> {
+        loop {
+            thread::sleep(self.interval);
+            let swaps = SwapList::new_from_file(&self.path)?;
+            if swaps != self.last {
+                self.last = swaps.clone();
+                return Ok(swaps);
+            }
+        }
     }
 }
 
 /// Iteratively parse the `/proc/swaps` file.
+///
+/// The first line is skipped if it is the header, so the file may be given with or without one.
 pub struct SwapIter<R: BufRead> {
-    file:   R,
-    buffer: String,
+    file:        R,
+    buffer:      String,
+    line_number: usize,
+    pending:     bool,
 }
 
 impl SwapIter<BufReader<File>> {
@@ -151,10 +517,27 @@ impl SwapIter<BufReader<File>> {
 impl<R: BufRead> SwapIter<R> {
     pub fn new_from_reader(mut reader: R) -> io::Result<Self> {
         let mut buffer = String::with_capacity(512);
-        reader.read_line(&mut buffer)?;
-        buffer.clear();
+        let mut line_number = 0;
+
+        // Skip any blank lines before the header, or the first entry if there is none.
+        let pending = loop {
+            buffer.clear();
+            if reader.read_line(&mut buffer)? == 0 {
+                break false;
+            }
 
-        Ok(Self { file: reader, buffer })
+            line_number += 1;
+            if !is_blank(&buffer) {
+                break !is_header(&buffer);
+            }
+        };
+
+        // The pending line is counted when it is returned by `next`.
+        if pending {
+            line_number -= 1;
+        }
+
+        Ok(Self { file: reader, buffer, line_number, pending })
     }
 }
 
@@ -162,11 +545,31 @@ impl<R: BufRead> Iterator for SwapIter<R> {
     type Item = io::Result<SwapInfo>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.buffer.clear();
-        match self.file.read_line(&mut self.buffer) {
-            Ok(read) if read == 0 => None,
-            Ok(_) => Some(SwapInfo::from_str(&self.buffer)),
-            Err(why) => Some(Err(why)),
+        if self.pending {
+            self.pending = false;
+            self.line_number += 1;
+            return Some(
+                SwapInfo::parse_numbered(&self.buffer, self.line_number).map_err(io::Error::from),
+            );
+        }
+
+        loop {
+            self.buffer.clear();
+            match self.file.read_line(&mut self.buffer) {
+                Ok(read) if read == 0 => return None,
+                Ok(_) => {
+                    self.line_number += 1;
+                    if is_blank(&self.buffer) {
+                        continue;
+                    }
+
+                    return Some(
+                        SwapInfo::parse_numbered(&self.buffer, self.line_number)
+                            .map_err(io::Error::from),
+                    );
+                }
+                Err(why) => return Some(Err(why)),
+            }
         }
     }
 }
@@ -174,7 +577,7 @@ impl<R: BufRead> Iterator for SwapIter<R> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::{ffi::OsString, path::PathBuf};
+    use std::{env, f64::EPSILON, ffi::OsString, fs, path::PathBuf, process};
 
     const SAMPLE: &str = r#"Filename				Type		Size	Used	Priority
 /dev/sda5                               partition	8388600	0	-2"#;
@@ -195,5 +598,242 @@ mod tests {
 
         assert!(swaps.get_swapped(Path::new("/dev/sda5")));
         assert!(!swaps.get_swapped(Path::new("/dev/sda1")));
+        assert_eq!(swaps.get_swap("/dev/sda5").map(|swap| swap.priority), Some(-2));
+        assert_eq!(swaps.get_swap("/dev/sda1"), None);
+        assert!(swaps[0].used_percent().abs() < EPSILON);
+        assert!(swaps.total_used_percent().abs() < EPSILON);
+    }
+
+    #[test]
+    fn header_detection() {
+        let with_header = SwapList::parse_with_header(SAMPLE.lines()).unwrap();
+        let without_header = SwapList::parse_with_header(SAMPLE.lines().skip(1)).unwrap();
+        assert_eq!(with_header, SwapList::parse_from(SAMPLE.lines().skip(1)).unwrap());
+        assert_eq!(without_header, with_header);
+        assert!(SwapList::parse_with_header("".lines()).unwrap().is_empty());
+
+        let headerless = SAMPLE.lines().nth(1).unwrap();
+        assert_eq!(SwapList::parse_reader(headerless.as_bytes()).unwrap(), with_header);
+        assert_eq!(SwapList::parse_reader(SAMPLE.as_bytes()).unwrap(), with_header);
+        assert!(SwapList::parse_reader(&b""[..]).unwrap().is_empty());
+
+        let error = SwapList::parse_reader(&b"/dev/sda5 partition x 0 -2\n"[..]).unwrap_err();
+        assert!(error.to_string().starts_with("line 1:"));
+    }
+
+    #[test]
+    fn blank_lines() {
+        let expected = SwapList::parse_from(SAMPLE.lines().skip(1)).unwrap();
+        let entry = SAMPLE.lines().nth(1).unwrap();
+
+        for contents in &[format!("\n{}\n\n", SAMPLE), format!("  \n{}\n", entry)] {
+            assert_eq!(SwapList::parse_with_header(contents.lines()).unwrap(), expected);
+            assert_eq!(SwapList::parse_reader(contents.as_bytes()).unwrap(), expected);
+        }
+
+        assert!(SwapList::parse_reader(&b"\n\n"[..]).unwrap().is_empty());
+
+        let error = SwapList::parse_reader(&b"\n/dev/sda5 partition x 0 -2\n"[..]).unwrap_err();
+        assert!(error.to_string().starts_with("line 2:"));
+        let error = SwapList::parse_reader(&b"\n/dev/sda5 partition 1 0 -2\n\nx"[..]).unwrap_err();
+        assert!(error.to_string().starts_with("line 4:"));
+    }
+
+    #[test]
+    fn human_sizes() {
+        let swaps = SwapList::parse_from(SAMPLE.lines().skip(1)).unwrap();
+        assert_eq!(swaps[0].size_human(), "8.0 GiB");
+        assert_eq!(swaps[0].used_human(), "0.0 KiB");
+
+        assert_eq!(format_kib(1023), "1023.0 KiB");
+        assert_eq!(format_kib(1024), "1.0 MiB");
+        assert_eq!(format_kib(3 << 30), "3.0 TiB");
+        assert_eq!(format_kib(5 << 40), "5120.0 TiB");
+    }
+
+    #[test]
+    fn by_free_space() {
+        let lines = "/dev/sda5 partition 8388600 8000000 -2\n/swapfile file 1048572 0 -3";
+        let swaps = SwapList::parse_from(lines.lines()).unwrap();
+        let order = swaps.by_free_space().iter().map(|swap| swap.used).collect::<Vec<_>>();
+        assert_eq!(order, vec![0, 8_000_000]);
+    }
+
+    #[test]
+    fn filter_by_kind() {
+        let lines = "/dev/sda5 partition 8388600 0 -2\n/swapfile file 1048572 0 -3\n/dev/zram0 \
+                     partition 4194300 0 100\n/var/swap file 524284 0 -4";
+        let swaps = SwapList::parse_from(lines.lines()).unwrap();
+
+        let files = swaps.files().map(|swap| swap.source.as_path()).collect::<Vec<_>>();
+        assert_eq!(files, vec![Path::new("/swapfile"), Path::new("/var/swap")]);
+
+        let partitions = swaps.partitions().map(|swap| swap.source.as_path()).collect::<Vec<_>>();
+        assert_eq!(partitions, vec![Path::new("/dev/sda5"), Path::new("/dev/zram0")]);
+    }
+
+    #[test]
+    fn diff() {
+        let old = SwapList::parse_from(SAMPLE.lines().skip(1)).unwrap();
+        let lines = "/dev/sda5 partition 8388600 1024 -2\n/swapfile file 1048572 0 -3";
+        let new = SwapList::parse_from(lines.lines()).unwrap();
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.added, vec![&new[1]]);
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.changed, vec![(&old[0], &new[0])]);
+        assert!(old.diff(&old).is_empty());
+
+        let diff = new.diff(&old);
+        assert_eq!(diff.removed, vec![&new[1]]);
+    }
+
+    #[test]
+    fn watcher() {
+        let path = env::temp_dir().join(format!("proc-mounts-swaps-{}", process::id()));
+        fs::write(&path, SAMPLE).unwrap();
+        let mut watcher = SwapWatcher::new_from_file(&path, Duration::from_millis(10)).unwrap();
+        assert_eq!(watcher.swaps().len(), 1);
+
+        fs::write(&path, format!("{}\n/swapfile file 1048572 0 -3\n", SAMPLE)).unwrap();
+        let swaps = watcher.wait().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(swaps.len(), 2);
+        assert_eq!(watcher.swaps(), &swaps);
+    }
+
+    #[test]
+    fn kind() {
+        let swaps = SwapList::parse_from(SAMPLE.lines().skip(1)).unwrap();
+        assert_eq!(swaps[0].kind_parsed(), SwapKind::Partition);
+        assert!(swaps[0].is_partition());
+        assert!(!swaps[0].is_file());
+
+        let swap = "/swapfile file 1048572 0 -3".parse::<SwapInfo>().unwrap();
+        assert_eq!(swap.kind_parsed(), SwapKind::File);
+        assert!(swap.is_file());
+        assert!(!swap.is_partition());
+
+        let swap = "/dev/zram0 zram 1048572 0 100".parse::<SwapInfo>().unwrap();
+        assert_eq!(swap.kind_parsed(), SwapKind::Other(OsString::from("zram")));
+        assert!(!swap.is_file() && !swap.is_partition());
+    }
+
+    #[test]
+    fn priority() {
+        let swaps = SwapList::parse_from(SAMPLE.lines().skip(1)).unwrap();
+        assert!(swaps[0].priority_is_auto());
+        assert!(!swaps[0].is_user_priority());
+
+        let swap = "/swapfile file 1048572 0 10".parse::<SwapInfo>().unwrap();
+        assert!(!swap.priority_is_auto());
+        assert!(swap.is_user_priority());
+    }
+
+    #[test]
+    fn kib_suffix() {
+        let swap = "/dev/sda5 partition 8388600kB 1024K -2".parse::<SwapInfo>().unwrap();
+        assert_eq!((swap.size, swap.used), (8_388_600, 1024));
+
+        let error = SwapInfo::parse_numbered("/dev/sda5 partition 8388600MB 0 -2", 1).unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::InvalidNumber {
+                field:       "size",
+                line_number: 1,
+                value:       "8388600MB".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let input = [SAMPLE, "/dev/sdb2 partition 1024 x -3"].join("\n");
+
+        let error = SwapList::parse_reader(input.as_bytes()).unwrap_err();
+        let error = error.get_ref().and_then(|e| e.downcast_ref::<ParseError>()).unwrap();
+        assert_eq!(
+            error,
+            &ParseError::InvalidNumber {
+                field:       "used",
+                line_number: 3,
+                value:       "x".into(),
+            }
+        );
+
+        let error = SwapInfo::parse_numbered("/dev/sda5 partition", 4).unwrap_err();
+        assert_eq!(error, ParseError::MissingField { field: "size", line_number: 4 });
+    }
+
+    #[test]
+    fn parse_reader() {
+        let swaps = SwapList::parse_reader(SAMPLE.as_bytes()).unwrap();
+        assert_eq!(swaps, SwapList::parse_from(SAMPLE.lines().skip(1)).unwrap());
+    }
+
+    #[test]
+    fn by_priority() {
+        let lines = [
+            "/dev/sda5 partition 8388600 0 -2",
+            "/swapfile file 1024 0 10",
+            "/dev/sdb1 partition 2048 0 -1",
+            "/dev/sdc1 partition 4096 0 -1",
+        ];
+
+        let swaps = SwapList::parse_from(lines.iter().cloned()).unwrap();
+
+        let sources = swaps.by_priority().iter().map(|s| s.source.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            sources,
+            vec![
+                PathBuf::from("/swapfile"),
+                PathBuf::from("/dev/sdb1"),
+                PathBuf::from("/dev/sdc1"),
+                PathBuf::from("/dev/sda5"),
+            ]
+        );
+    }
+
+    #[test]
+    fn totals() {
+        const SAMPLE: &str = r#"Filename				Type		Size	Used	Priority
+/dev/sda5                               partition	8388600	1024	-2
+/swapfile                               file		2097148	512	10"#;
+
+        let swaps = SwapList::parse_from(SAMPLE.lines().skip(1)).unwrap();
+        assert_eq!(swaps.total_size(), 10_485_748);
+        assert_eq!(swaps.total_used(), 1536);
+        assert_eq!(swaps.total_free(), 10_484_212);
+        assert!((swaps[1].used_percent() - 512.0 / 2_097_148.0 * 100.0).abs() < EPSILON);
+        assert!((swaps.total_used_percent() - 1536.0 / 10_485_748.0 * 100.0).abs() < EPSILON);
+        assert!(SwapList(Vec::new()).total_used_percent().abs() < EPSILON);
+    }
+
+    #[test]
+    fn is_nearly_full() {
+        let swaps = SwapList::parse_from(["/dev/sda5 partition 1000 900 -2"].iter().cloned());
+        let swaps = swaps.unwrap();
+        assert!(swaps.is_nearly_full(80.0));
+        assert!(!swaps.is_nearly_full(90.0));
+        // A fraction is read as a percentage, so 90% used is over a threshold of 0.8%.
+        assert!(swaps.is_nearly_full(0.8));
+        assert!(!SwapList(Vec::new()).is_nearly_full(0.0));
+    }
+
+    #[test]
+    fn len() {
+        let swaps = SwapList::parse_from(SAMPLE.lines().skip(1)).unwrap();
+        assert_eq!(swaps.len(), 1);
+        assert!(!swaps.is_empty());
+        assert!(SwapList(Vec::new()).is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let swaps = SwapList::parse_from(SAMPLE.lines().skip(1)).unwrap();
+        let json = serde_json::to_string(&swaps).unwrap();
+        assert_eq!(serde_json::from_str::<SwapList>(&json).unwrap(), swaps);
     }
 }